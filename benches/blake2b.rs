@@ -0,0 +1,130 @@
+mod baseline;
+
+use crate::baseline::B2Vec;
+use crypto_api_blake2::{ Blake2b, Blake2bBackend, Blake2bJob, Blake2bMulti };
+use std::{ hint::black_box, iter, time::Instant };
+
+
+/// Runs `f` `iterations` times and returns the throughput in MiB/s
+fn bench(iterations: usize, bytes: usize, mut f: impl FnMut()) -> f64 {
+	// Warm up
+	for _ in 0..iterations / 10 { f() }
+	
+	// Measure
+	let start = Instant::now();
+	for _ in 0..iterations { f() }
+	(bytes * iterations) as f64 / start.elapsed().as_secs_f64() / (1024.0 * 1024.0)
+}
+
+
+/// Hashes `len` bytes `iterations` times
+fn bench_hash(len: usize, iterations: usize) -> f64 {
+	let (hash, data, mut buf) = (Blake2b::hash(), vec![0x2a; len], [0; 64]);
+	bench(iterations, len, || {
+		hash.hash(&mut buf, black_box(&data)).unwrap();
+		black_box(&buf);
+	})
+}
+
+/// Absorbs `len` bytes into a streaming hash `iterations` times
+fn bench_streaming(len: usize, iterations: usize) -> f64 {
+	let (mut hash, data) = (Blake2b::streaming_hash(), vec![0x2a; len]);
+	hash.init().unwrap();
+	bench(iterations, len, || hash.update(black_box(&data)).unwrap())
+}
+
+/// Hashes `len` bytes `iterations` times using the heap-based baseline core
+fn bench_hash_baseline(len: usize, iterations: usize) -> f64 {
+	let (data, mut buf) = (vec![0x2a; len], [0; 64]);
+	bench(iterations, len, || {
+		B2Vec::init(64).update(black_box(&data)).finish(&mut buf);
+		black_box(&buf);
+	})
+}
+
+/// Absorbs `len` bytes into a streaming hash `iterations` times using the heap-based baseline core
+fn bench_streaming_baseline(len: usize, iterations: usize) -> f64 {
+	let (mut hash, data) = (B2Vec::init(64), vec![0x2a; len]);
+	bench(iterations, len, || { hash.update(black_box(&data)); })
+}
+
+/// Hashes 1024 messages with `len` bytes each `iterations` times using the multi-buffer API
+fn bench_multi(len: usize, iterations: usize) -> f64 {
+	let (data, mut bufs) = (vec![0x2a; len], vec![[0; 64]; 1024]);
+	bench(iterations, len * bufs.len(), || {
+		let mut jobs: Vec<Blake2bJob> = bufs.iter_mut().map(|buf| Blake2bJob::new(buf, black_box(&data))).collect();
+		Blake2bMulti::hash(&mut jobs).unwrap();
+		black_box(&bufs);
+	})
+}
+
+
+/// A benchmark workload with its name, input length and iteration count
+enum Workload {
+	Hash(&'static str, usize, usize),
+	Streaming(&'static str, usize, usize),
+	Multi(&'static str, usize, usize)
+}
+impl Workload {
+	/// The name of the workload
+	fn name(&self) -> &'static str {
+		match self {
+			Self::Hash(name, ..) | Self::Streaming(name, ..) | Self::Multi(name, ..) => name
+		}
+	}
+	/// Runs the workload with `backend` (or the baseline core if `backend` is `None`) and returns
+	/// the throughput in MiB/s (or `None` if the baseline does not support the workload)
+	fn run(&self, backend: Option<Blake2bBackend>) -> Option<f64> {
+		if let Some(backend) = backend { Blake2bBackend::force(backend).unwrap() }
+		match (self, backend) {
+			(Self::Hash(_, len, iterations), Some(_)) => Some(bench_hash(*len, *iterations)),
+			(Self::Hash(_, len, iterations), None) => Some(bench_hash_baseline(*len, *iterations)),
+			(Self::Streaming(_, len, iterations), Some(_)) => Some(bench_streaming(*len, *iterations)),
+			(Self::Streaming(_, len, iterations), None) => Some(bench_streaming_baseline(*len, *iterations)),
+			(Self::Multi(_, len, iterations), Some(_)) => Some(bench_multi(*len, *iterations)),
+			(Self::Multi(..), None) => None
+		}
+	}
+}
+
+
+fn main() {
+	// Ensure that the baseline computes the same digests
+	let (data, mut expected, mut buf) = (vec![0x2a; 1000], [0; 64], [0; 64]);
+	Blake2b::hash().hash(&mut expected, &data).unwrap();
+	B2Vec::init(64).update(&data).finish(&mut buf);
+	assert_eq!(buf, expected, "The baseline is invalid");
+	
+	let workloads = [
+		Workload::Hash("blake2b 16 B", 16, 2_000_000),
+		Workload::Hash("blake2b 64 B", 64, 2_000_000),
+		Workload::Hash("blake2b 256 B", 256, 1_000_000),
+		Workload::Hash("blake2b 1 KiB", 1024, 300_000),
+		Workload::Hash("blake2b 1 MiB", 1024 * 1024, 300),
+		Workload::Streaming("blake2b update 1 KiB", 1024, 300_000),
+		Workload::Streaming("blake2b update 64 KiB", 64 * 1024, 5_000),
+		Workload::Streaming("blake2b update 16 MiB", 16 * 1024 * 1024, 20),
+		Workload::Multi("blake2b multi 64 B", 64, 2_000),
+		Workload::Multi("blake2b multi 1 KiB", 1024, 300)
+	];
+	
+	// Run each workload with the baseline and each available backend forced in turn
+	let backends: Vec<Option<Blake2bBackend>> = iter::once(None)
+		.chain(Blake2bBackend::ALL.iter().copied().filter(|b| b.is_available()).map(Some))
+		.collect();
+	print!("{:<24}", "MiB/s");
+	for backend in backends.iter() {
+		print!(" {:>10}", backend.map(|b| b.name()).unwrap_or("baseline"));
+	}
+	println!();
+	for workload in workloads.iter() {
+		print!("{:<24}", workload.name());
+		for backend in backends.iter() {
+			match workload.run(*backend) {
+				Some(throughput) => print!(" {:>10.1}", throughput),
+				None => print!(" {:>10}", "-")
+			}
+		}
+		println!();
+	}
+}
@@ -0,0 +1,94 @@
+//! This crate uses the core of `crypto_api_blake2` without `std`; since it defines its own panic
+//! handler, it fails to build if `std` is linked by accident.
+#![cfg_attr(not(test), no_std)]
+
+use crypto_api_blake2::{ Blake2Error, Blake2Xb, Blake2bParams, Blake2sHasher };
+
+
+/// Computes the 512-bit Blake2b hash over `data`
+pub fn hash(buf: &mut[u8; 64], data: &[u8]) -> Result<(), Blake2Error> {
+	let mut state = Blake2bParams::new().to_state()?;
+	state.update(data).finish(buf)?;
+	Ok(())
+}
+/// Computes the 256-bit Blake2b hash over `chunks`
+pub fn streaming_hash(buf: &mut[u8; 32], chunks: &[&[u8]]) -> Result<(), Blake2Error> {
+	let mut state = Blake2bParams::new().hash_len(32).to_state()?;
+	for chunk in chunks { state.update(chunk); }
+	state.finish(buf)?;
+	Ok(())
+}
+/// Computes the 256-bit Blake2b MAC over `data` with `key`
+pub fn mac(buf: &mut[u8; 32], data: &[u8], key: &[u8]) -> Result<(), Blake2Error> {
+	let mut state = Blake2bParams::new().hash_len(32).key(key).to_state()?;
+	state.update(data).finish(buf)?;
+	Ok(())
+}
+/// Derives a 256-bit key from `base_key`, `salt` and `info`
+pub fn kdf(buf: &mut[u8; 32], base_key: &[u8], salt: &[u8], info: &[u8]) -> Result<(), Blake2Error> {
+	let mut state = Blake2bParams::new().hash_len(32).key(base_key).salt(salt).personal(info).to_state()?;
+	state.finish(buf)?;
+	Ok(())
+}
+/// Computes the 256-bit Blake2s hash over `data`
+pub fn blake2s_hash(buf: &mut[u8; 32], data: &[u8]) -> Result<(), Blake2Error> {
+	let mut hasher = Blake2sHasher::new(32)?;
+	hasher.update(data);
+	buf.copy_from_slice(&hasher.finalize());
+	Ok(())
+}
+/// Computes the Blake2Xb output over `data` into `buf`
+pub fn xof(buf: &mut[u8], data: &[u8]) -> Result<(), Blake2Error> {
+	Blake2Xb::hash(buf, data)
+}
+
+
+#[cfg(not(test))]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+	loop { core::hint::spin_loop() }
+}
+
+
+#[cfg(test)]
+mod tests {
+	/// Decodes `hex`
+	fn hex(hex: &str) -> Vec<u8> {
+		(0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i .. i + 2], 16).unwrap()).collect()
+	}
+	
+	#[test]
+	fn test() {
+		// Hash
+		let mut buf = [0; 64];
+		super::hash(&mut buf, b"abc").unwrap();
+		assert_eq!(buf.to_vec(), hex("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"));
+		
+		// Streaming hash
+		let mut buf = [0; 32];
+		super::streaming_hash(&mut buf, &[b"Testo", b"lope"]).unwrap();
+		let mut expected = [0; 32];
+		super::streaming_hash(&mut expected, &[b"Testolope"]).unwrap();
+		assert_eq!(buf, expected);
+		
+		// MAC
+		let mut buf = [0; 32];
+		super::mac(&mut buf, b"Testolope", b"Key").unwrap();
+		assert_eq!(buf.to_vec(), hex("ea8c75a285108d9567599eeff7c63090e58e81f2ba1a8d5835becf681341320b"));
+		
+		// KDF
+		let mut buf = [0; 32];
+		super::kdf(&mut buf, b"Base key", b"Salt", b"Info").unwrap();
+		assert_eq!(buf.to_vec(), hex("8fcff2402fd1f3436f3875761704068043676dc0a2d8cdbade7fbba193fd5976"));
+		
+		// Blake2s
+		let mut buf = [0; 32];
+		super::blake2s_hash(&mut buf, b"abc").unwrap();
+		assert_eq!(buf.to_vec(), hex("508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"));
+		
+		// XOF
+		let mut buf = [0; 100];
+		super::xof(&mut buf, b"abc").unwrap();
+		assert_eq!(buf.to_vec(), hex("e0f82b71c07860b65be612d2633becc46596a6c12a8772b561adec35721b7a5c44a7e075e8a3bc8c4fc8390a197be2085b4aa4385c207f24e46415defc659afd73bacb288080b10849aeea386c60cd3fa04c9bcbfeebaed6e98634d696b9d5bdef0ad2c5"));
+	}
+}
\ No newline at end of file
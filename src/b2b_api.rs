@@ -1,4 +1,4 @@
-use crate::{ Blake2Error, b2b_impl::B2Impl };
+use crate::{ Blake2Error, Blake2bKey, Blake2bState, b2b_impl::B2Impl };
 use crypto_api::{
 	rng::{ SecureRng, SecKeyGen }, kdf::{ KdfInfo, Kdf },
 	mac::{ MacInfo, Mac, VarlenMac, StreamingMac, StreamingVarlenMac },
@@ -7,66 +7,137 @@ use crypto_api::{
 use std::error::Error;
 
 
-/// Checks if `$v` is in the range `$r`
-macro_rules! check_in {
-    ($v:expr, $r:expr) => ({
-    	if $v < *$r.start() {
-    		Err(Blake2Error::ApiMisuse(concat!("`", stringify!($v), "` is too small")))?
-    	}
-    	if $v > *$r.end() {
-    		Err(Blake2Error::ApiMisuse(concat!("`", stringify!($v), "` is too large")))?
-    	}
-    });
-}
-/// Checks if `$v` is equal to `$e`
-macro_rules! check_eq {
-    ($v:expr, $e:expr) => ({
-    	if $v != $e {
-    		Err(Blake2Error::ApiMisuse(concat!("`", stringify!($v), "` is invalid")))?
-    	}
-    })
-}
-
-
 /// An implementation of [Blake2b](https://blake2.net/blake2.pdf)
-pub struct Blake2b(Option<B2Impl>);
+///
+/// _Note: Unlike the boxed trait objects, `Blake2b` itself is `Clone`: to reuse a common prefix,
+/// initialize and update a `Blake2b::default()` via the streaming traits and finish clones of it_
+#[derive(Default, Clone)]
+pub struct Blake2b {
+	state: Option<B2Impl>,
+	initial: Option<B2Impl>,
+	personal: Vec<u8>,
+	long_keys: bool
+}
 impl Blake2b {
+	/// Creates a new `Blake2b` instance that uses `personal` as personalization string for all hash
+	/// and MAC operations
+	///
+	/// _Note: The personalization string must not be longer than 16 bytes; the KDF always uses its
+	/// `info` argument as personalization string instead_
+	pub fn with_personal(personal: &[u8]) -> Result<Self, Blake2Error> {
+		check_in!(personal.len(), 0..=16);
+		Ok(Self { state: None, initial: None, personal: personal.to_vec(), long_keys: false })
+	}
+	/// Creates a new `Blake2b` instance whose MAC operations accept keys with an arbitrary length
+	/// (`1..`) by pre-hashing them into 64 byte keys (see `Blake2bKey::prehash`)
+	///
+	/// _Note: This is a distinct keyed mode that produces different MACs than the native keyed mode
+	/// for all keys (including keys that are not longer than 64 bytes); the KDF is not affected_
+	pub fn with_long_keys() -> Self {
+		Self { long_keys: true, ..Self::default() }
+	}
+	
 	/// Creates a `Hash` instance with `Blake2b` as underlying hash
 	pub fn hash() -> Box<dyn Hash> {
-		Box::new(Self(None))
+		Box::new(Self::default())
 	}
 	/// Creates a `VarlenHash` instance with `Blake2b` as underlying hash
 	pub fn varlen_hash() -> Box<dyn VarlenHash> {
-		Box::new(Self(None))
+		Box::new(Self::default())
 	}
 	/// Creates a `Mac` instance with `Blake2b` as underlying MAC
 	pub fn mac() -> Box<dyn Mac> {
-		Box::new(Self(None))
+		Box::new(Self::default())
 	}
 	/// Creates a `VarlenMac` instance with `Blake2b` as underlying MAC
 	pub fn varlen_mac() -> Box<dyn VarlenMac> {
-		Box::new(Self(None))
+		Box::new(Self::default())
 	}
 	/// Creates a `Kdf` instance with `Blake2b` as underlying KDF
 	pub fn kdf() -> Box<dyn Kdf> {
-		Box::new(Self(None))
+		Box::new(Self::default())
 	}
 	
 	/// Creates a `StreamingHash` instance with `Blake2b` as underlying hash
 	pub fn streaming_hash() -> Box<dyn StreamingHash> {
-		Box::new(Self(None))
+		Box::new(Self::default())
 	}
 	/// Creates a `StreamingVarlenHash` instance with `Blake2b` as underlying hash
 	pub fn streaming_varlen_hash() -> Box<dyn StreamingVarlenHash> {
-		Box::new(Self(None))
+		Box::new(Self::default())
 	}
 	/// Creates a `StreamingMac` instance with `Blake2b` as underlying MAC
 	pub fn streaming_mac() -> Box<dyn StreamingMac> {
-		Box::new(Self(None))
+		Box::new(Self::default())
 	}
 	/// Creates a `StreamingVarlenMac` instance with `Blake2b` as underlying MAC
 	pub fn streaming_varlen_mac() -> Box<dyn StreamingVarlenMac> {
-		Box::new(Self(None))
+		Box::new(Self::default())
+	}
+	
+	/// Computes the 64 byte MAC of `data` with `key` and compares it with `tag` in constant time
+	/// (returns `Blake2Error::InvalidTag` if the tag does not match)
+	pub fn verify(&self, tag: &[u8], data: &[u8], key: &[u8]) -> Result<(), Blake2Error> {
+		self.init_mac(64, key)?.update(data).finish_verify(tag)
+	}
+	/// Computes the MAC of `data` with `key` and the length of `tag` (`1..=64` bytes) and compares it
+	/// with `tag` in constant time (returns `Blake2Error::InvalidTag` if the tag does not match)
+	pub fn varlen_verify(&self, tag: &[u8], data: &[u8], key: &[u8]) -> Result<(), Blake2Error> {
+		check_in!(tag.len(), 1..=64);
+		self.init_mac(tag.len(), key)?.update(data).finish_verify(tag)
+	}
+	/// Finalizes the streaming MAC and compares it with `tag` in constant time (returns
+	/// `Blake2Error::InvalidTag` if the tag does not match)
+	pub fn finish_verify(&mut self, tag: &[u8]) -> Result<(), Blake2Error> {
+		let mut state = self.state.take()
+			.ok_or(Blake2Error::ApiMisuse("The MAC is not initialized"))?;
+		state.finish_verify(tag)
+	}
+	
+	/// Resets the streaming hash or MAC to its initial state after the last `init` (i.e. with the same
+	/// digest length and key) so that it can be reused after `finish`
+	///
+	/// _Note: The key block is only compressed once for all resets (see `Blake2bState::reset`)_
+	pub fn reset(&mut self) -> Result<(), Blake2Error> {
+		let initial = self.initial.as_mut()
+			.ok_or(Blake2Error::ApiMisuse("The state is not initialized"))?;
+		initial.precompress();
+		self.state = Some(initial.clone());
+		Ok(())
+	}
+	/// Exports the initialized streaming hash or MAC (see `Blake2bState::export`)
+	pub fn export(&self) -> Result<[u8; Blake2bState::EXPORT_LEN], Blake2Error> {
+		let state = self.state.as_ref()
+			.ok_or(Blake2Error::ApiMisuse("The state is not initialized"))?;
+		Ok(state.export())
+	}
+	/// Validates and imports a streaming hash or MAC that has been exported using `export` (see
+	/// `Blake2bState::import`)
+	///
+	/// _Note: The imported state is also the initial state for `reset`_
+	pub fn import(bytes: &[u8]) -> Result<Self, Blake2Error> {
+		let mut this = Self::default();
+		this.set_state(B2Impl::import(bytes)?);
+		Ok(this)
+	}
+	/// Sets `state` as the current and the initial streaming state
+	fn set_state(&mut self, state: B2Impl) {
+		self.initial = Some(state.clone());
+		self.state = Some(state);
+	}
+	
+	/// Validates `key` and initializes a MAC state with `mac_len` and `key` (which is pre-hashed if
+	/// long keys are enabled)
+	fn init_mac(&self, mac_len: usize, key: &[u8]) -> Result<B2Impl, Blake2Error> {
+		if !self.long_keys {
+			check_in!(key.len(), 1..=64);
+			return Ok(B2Impl::init_mac(mac_len, key, &self.personal));
+		}
+		
+		if key.is_empty() {
+			Err(Blake2Error::ApiMisuse("`key.len()` is too small"))?
+		}
+		Ok(B2Impl::init_mac(mac_len, &Blake2bKey::prehash(key), &self.personal))
 	}
 	
 	/// Returns info about the hash
@@ -74,8 +145,9 @@ impl Blake2b {
 		HashInfo { name: "Blake2b", hash_len: 64, hash_len_r: 1..(64 + 1) }
 	}
 	/// Returns info about the MAC
-	fn mac_info() -> MacInfo {
-		MacInfo { name: "Blake2b", is_otm: false, mac_len: 64, mac_len_r: 1..(64 + 1), key_len_r: 1..(64 + 1) }
+	fn mac_info(&self) -> MacInfo {
+		let key_len_r = if self.long_keys { 1..usize::MAX } else { 1..(64 + 1) };
+		MacInfo { name: "Blake2b", is_otm: false, mac_len: 64, mac_len_r: 1..(64 + 1), key_len_r }
 	}
 	/// Returns info about the KDF
 	fn kdf_info() -> KdfInfo {
@@ -85,7 +157,6 @@ impl Blake2b {
 		}
 	}
 }
-
 impl SecKeyGen for Blake2b {
 	fn new_sec_key(&self, buf: &mut[u8], rng: &mut dyn SecureRng) -> Result<usize, Box<dyn Error + 'static>> {
 		check_in!(buf.len(), 1..=64);
@@ -109,14 +180,14 @@ impl VarlenHash for Blake2b {
 	fn varlen_hash(&self, buf: &mut[u8], data: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
 		check_in!(buf.len(), 1..=64);
 		
-		B2Impl::init(buf.len()).update(data).finish(buf);
+		B2Impl::init(buf.len(), &self.personal).update(data).finish(buf);
 		Ok(buf.len())
 	}
 }
 
 impl Mac for Blake2b {
 	fn info(&self) -> MacInfo {
-		Self::mac_info()
+		self.mac_info()
 	}
 	
 	fn auth(&self, buf: &mut[u8], data: &[u8], key: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
@@ -127,9 +198,8 @@ impl Mac for Blake2b {
 impl VarlenMac for Blake2b {
 	fn varlen_auth(&self, buf: &mut[u8], data: &[u8], key: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
 		check_in!(buf.len(), 1..=64);
-		check_in!(key.len(), 1..=64);
 		
-		B2Impl::init_mac(buf.len(), key).update(data).finish(buf);
+		self.init_mac(buf.len(), key)?.update(data).finish(buf);
 		Ok(buf.len())
 	}
 }
@@ -153,22 +223,22 @@ impl Kdf for Blake2b {
 impl StreamingHash for Blake2b {
 	fn info(&self) -> HashInfo {
 		let mut info = Self::hash_info();
-		if let Some(s) = self.0.as_ref() { info.hash_len = s.hash_len() }
+		if let Some(s) = self.state.as_ref() { info.hash_len = s.hash_len() }
 		info
 	}
 	
 	fn init(&mut self) -> Result<(), Box<dyn Error + 'static>> {
-		self.0 = Some(B2Impl::init(64));
+		self.set_state(B2Impl::init(64, &self.personal));
 		Ok(())
 	}
 	fn update(&mut self, input: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
-		self.0.as_mut()
+		self.state.as_mut()
 			.ok_or(Blake2Error::ApiMisuse("The hash is not initialized"))?
 			.update(input);
 		Ok(())
 	}
 	fn finish(&mut self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
-		let mut state = self.0.take()
+		let mut state = self.state.take()
 			.ok_or(Blake2Error::ApiMisuse("The hash is not initialized"))?;
 		check_eq!(buf.len(), state.hash_len());
 		
@@ -180,32 +250,31 @@ impl StreamingVarlenHash for Blake2b {
 	fn varlen_init(&mut self, hash_len: usize) -> Result<(), Box<dyn Error + 'static>> {
 		check_in!(hash_len, 1..=64);
 		
-		self.0 = Some(B2Impl::init(hash_len));
+		self.set_state(B2Impl::init(hash_len, &self.personal));
 		Ok(())
 	}
 }
 
 impl StreamingMac for Blake2b {
 	fn info(&self) -> MacInfo {
-		let mut info = Self::mac_info();
-		if let Some(s) = self.0.as_ref() { info.mac_len = s.hash_len() }
+		let mut info = self.mac_info();
+		if let Some(s) = self.state.as_ref() { info.mac_len = s.hash_len() }
 		info
 	}
 	
 	fn init(&mut self, key: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
-		check_in!(key.len(), 1..=64);
-		
-		self.0 = Some(B2Impl::init_mac(64, key));
+		let state = self.init_mac(64, key)?;
+		self.set_state(state);
 		Ok(())
 	}
 	fn update(&mut self, data: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
-		self.0.as_mut()
+		self.state.as_mut()
 			.ok_or(Blake2Error::ApiMisuse("The MAC is not initialized"))?
 			.update(data);
 		Ok(())
 	}
 	fn finish(&mut self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
-		let mut state = self.0.take()
+		let mut state = self.state.take()
 			.ok_or(Blake2Error::ApiMisuse("The MAC is not initialized"))?;
 		check_eq!(buf.len(), state.hash_len());
 		
@@ -216,9 +285,9 @@ impl StreamingMac for Blake2b {
 impl StreamingVarlenMac for Blake2b {
 	fn varlen_init(&mut self, mac_len: usize, key: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
 		check_in!(mac_len, 1..=64);
-		check_in!(key.len(), 1..=64);
 		
-		self.0 = Some(B2Impl::init_mac(mac_len, key));
+		let state = self.init_mac(mac_len, key)?;
+		self.set_state(state);
 		Ok(())
 	}
 }
\ No newline at end of file
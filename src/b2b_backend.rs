@@ -0,0 +1,189 @@
+use crate::{ Blake2Error, b2b_impl, wipe::wipe };
+use core::sync::atomic::{ AtomicU8, Ordering::Relaxed };
+
+
+/// The selected backend (`0` if no backend has been selected yet)
+static BACKEND: AtomicU8 = AtomicU8::new(0);
+/// The environment variable that overrides the detected backend
+#[cfg(feature = "std")]
+const BACKEND_VAR: &str = "CRYPTO_API_BLAKE2_BACKEND";
+
+
+/// Checks at runtime (or at compile time without `std`) whether the CPU supports `$feature`
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+macro_rules! has_feature {
+	($feature:tt) => ({ std::is_x86_feature_detected!($feature) });
+}
+/// Checks at runtime (or at compile time without `std`) whether the CPU supports `$feature`
+#[cfg(all(not(feature = "std"), any(target_arch = "x86", target_arch = "x86_64")))]
+macro_rules! has_feature {
+	($feature:tt) => ({ cfg!(target_feature = $feature) });
+}
+
+
+/// The Blake2b compression backends
+#[repr(u8)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Blake2bBackend {
+	/// The portable scalar implementation
+	Portable = 1,
+	/// The SSE4.1 implementation (x86/x86_64 only)
+	Sse41 = 2,
+	/// The AVX2 implementation (x86/x86_64 only)
+	Avx2 = 3,
+	/// The AVX-512VL implementation with native 64 bit rotations (x86/x86_64 only)
+	Avx512 = 4
+}
+impl Blake2bBackend {
+	/// All backends from the slowest to the fastest one
+	pub const ALL: [Self; 4] = [Self::Portable, Self::Sse41, Self::Avx2, Self::Avx512];
+	
+	/// Whether the backend is supported by the current CPU or not
+	///
+	/// _Note: Without `std`, the SIMD backends are only available if the corresponding target
+	/// features are enabled at compile time_
+	pub fn is_available(self) -> bool {
+		match self {
+			Self::Portable => true,
+			#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+			Self::Sse41 => has_feature!("sse4.1"),
+			#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+			Self::Avx2 => has_feature!("avx2"),
+			#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+			Self::Avx512 => has_feature!("avx512vl"),
+			#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+			_ => false
+		}
+	}
+	/// The name of the backend (`portable`, `sse4.1`, `avx2` or `avx512`)
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Portable => "portable",
+			Self::Sse41 => "sse4.1",
+			Self::Avx2 => "avx2",
+			Self::Avx512 => "avx512"
+		}
+	}
+	/// The backend with the given `name` (see `name`)
+	pub fn from_name(name: &str) -> Result<Self, Blake2Error> {
+		match Self::ALL.iter().copied().find(|backend| backend.name() == name) {
+			Some(backend) => Ok(backend),
+			None => Err(Blake2Error::ApiMisuse("Unknown backend name"))
+		}
+	}
+	
+	/// The fastest backend that is supported by the current CPU
+	pub fn detect() -> Self {
+		Self::ALL.iter().rev().copied().find(|backend| backend.is_available())
+			.expect("The portable backend is always available")
+	}
+	
+	/// The backend that is currently used by all Blake2b states
+	///
+	/// _Note: Unless a backend is forced, the backend is selected on first use; with `std`, the
+	/// environment variable `CRYPTO_API_BLAKE2_BACKEND` can name a backend that is used instead of
+	/// the detected one (if the backend is unknown or not supported by the CPU, the detected backend
+	/// is used silently; see `from_env` to check the variable)_
+	pub fn current() -> Self {
+		match BACKEND.load(Relaxed) {
+			0 => {
+				let backend = Self::select();
+				BACKEND.store(backend as u8, Relaxed);
+				backend
+			},
+			2 => Self::Sse41,
+			3 => Self::Avx2,
+			4 => Self::Avx512,
+			_ => Self::Portable
+		}
+	}
+	/// Forces all Blake2b states to use `backend` instead of the detected one (e.g. for testing and
+	/// benchmarking)
+	pub fn force(backend: Self) -> Result<(), Blake2Error> {
+		if !backend.is_available() {
+			Err(Blake2Error::ApiMisuse("The backend is not supported by the current CPU"))?
+		}
+		BACKEND.store(backend as u8, Relaxed);
+		Ok(())
+	}
+	
+	/// The backend named by the environment variable `CRYPTO_API_BLAKE2_BACKEND` (or `None` if the
+	/// variable is not set)
+	///
+	/// _Note: This returns an error if the named backend is unknown or not supported by the current
+	/// CPU; `current` silently falls back to the detected backend in this case, so this can be used to
+	/// check the configuration_
+	#[cfg(feature = "std")]
+	pub fn from_env() -> Result<Option<Self>, Blake2Error> {
+		let backend = match std::env::var(BACKEND_VAR) {
+			Ok(name) => Self::from_name(&name)?,
+			Err(std::env::VarError::NotPresent) => return Ok(None),
+			Err(std::env::VarError::NotUnicode(_)) => Err(Blake2Error::ApiMisuse("Unknown backend name"))?
+		};
+		if !backend.is_available() {
+			Err(Blake2Error::ApiMisuse("The backend is not supported by the current CPU"))?
+		}
+		Ok(Some(backend))
+	}
+	
+	/// Selects the backend named by the environment variable or detects the fastest backend
+	///
+	/// _Note: This falls back to the detected backend if the named backend is unknown or not supported
+	/// so that a misconfigured environment cannot crash the hash functions_
+	#[cfg(feature = "std")]
+	fn select() -> Self {
+		match Self::from_env() {
+			Ok(Some(backend)) => backend,
+			_ => Self::detect()
+		}
+	}
+	/// Detects the fastest backend
+	#[cfg(not(feature = "std"))]
+	fn select() -> Self {
+		Self::detect()
+	}
+}
+
+
+/// The compression function; compresses the message block `m` into `h` using the current backend
+pub fn compress(h: &mut[u64; 8], m: &[u64; 16], t: &[u64; 2], f: &[u64; 2]) {
+	match Blake2bBackend::current() {
+		// Safety: Only available backends can be selected
+		#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+		Blake2bBackend::Avx512 => unsafe { crate::b2b_x86::compress_avx512(h, m, t, f) },
+		#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+		Blake2bBackend::Avx2 => unsafe { crate::b2b_x86::compress_avx2(h, m, t, f) },
+		#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+		Blake2bBackend::Sse41 => unsafe { crate::b2b_x86::compress_sse41(h, m, t, f) },
+		_ => {
+			let mut v = [0; 16];
+			b2b_impl::compress_portable(h, m, t, f, &mut v);
+			wipe(&mut v);
+		}
+	}
+}
+/// The multi-state compression function; compresses the message blocks `m` into the `LANES`
+/// independent states `h` using the current backend
+///
+/// _Note: Only the lanes that are marked in `active` need to be compressed; the SIMD backends
+/// compress all lanes at once whereas the scalar fallback skips the idle lanes_
+pub fn compress_many<const LANES: usize>(h: &mut[[u64; 8]; LANES], m: &[[u64; 16]; LANES], t: &[[u64; 2]; LANES], f: &[[u64; 2]; LANES], active: &[bool; LANES]) {
+	/// Converts the lane slice `$x` into an array with the matching lane count
+	#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+	macro_rules! lanes {
+		($x:expr) => ({ core::convert::TryInto::try_into($x).expect("Invalid lane count") });
+	}
+	
+	match (Blake2bBackend::current(), LANES) {
+		// Safety: Only available backends can be selected and the lane count has been checked
+		#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+		(Blake2bBackend::Avx512, 8) => unsafe {
+			crate::b2b_x86::compress8_avx512(lanes!(&mut h[..]), lanes!(&m[..]), lanes!(&t[..]), lanes!(&f[..]))
+		},
+		#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+		(Blake2bBackend::Avx2, 4) | (Blake2bBackend::Avx512, 4) => unsafe {
+			crate::b2b_x86::compress4_avx2(lanes!(&mut h[..]), lanes!(&m[..]), lanes!(&t[..]), lanes!(&f[..]))
+		},
+		_ => for i in (0..LANES).filter(|i| active[*i]) { compress(&mut h[i], &m[i], &t[i], &f[i]) }
+	}
+}
\ No newline at end of file
@@ -0,0 +1,70 @@
+use crate::b2b_impl::{ self, IV };
+
+
+/// A compile-time [Blake2b](https://blake2.net/blake2.pdf) implementation
+///
+/// All functions are `const fn`, so the digests can be evaluated into `[u8; N]` constants; invalid
+/// digest or key lengths panic (or fail the compilation if evaluated at compile time). The digests
+/// are identical to those of `VarlenHash::varlen_hash` (or `VarlenMac::varlen_auth` for keyed
+/// hashes).
+///
+/// _Note: Since volatile writes are not possible in `const fn`, the temporary state is not wiped;
+/// use `Blake2bFixed` for secret keys at runtime_
+pub struct Blake2bConst;
+impl Blake2bConst {
+	/// Computes the `N` byte digest of `data` (`N` must be in `1..=64`)
+	pub const fn hash<const N: usize>(data: &[u8]) -> [u8; N] {
+		Self::auth(data, &[])
+	}
+	/// Computes the `N` byte keyed digest of `data` with `key` (`N` must be in `1..=64`, the key
+	/// length in `0..=64`; an empty key disables keyed hashing)
+	pub const fn auth<const N: usize>(data: &[u8], key: &[u8]) -> [u8; N] {
+		// Validate the digest and key length
+		assert!(N >= 1 && N <= 64, "The digest length is invalid");
+		assert!(key.len() <= 64, "The key is too large");
+		
+		// Xor the parameter block (digest length, key length, fan-out and depth) with the IV
+		let mut h = IV;
+		h[0] ^= 0x0101_0000 | (key.len() as u64) << 8 | N as u64;
+		
+		// Compress all blocks of the message (including the `0`-padded key block if the key is set)
+		let len = if key.is_empty() { 0 } else { 128 } + data.len();
+		let blocks = if len == 0 { 1 } else { len.div_ceil(128) };
+		let (mut v, mut i) = ([0; 16], 0);
+		while i < blocks {
+			let is_last = i + 1 == blocks;
+			let t = if is_last { len as u128 } else { (i as u128 + 1) * 128 };
+			let f = if is_last { u64::MAX } else { 0 };
+			b2b_impl::compress_portable(&mut h, &Self::load(data, key, i * 128), &[t as u64, (t >> 64) as u64], &[f, 0], &mut v);
+			i += 1;
+		}
+		
+		// Store the first `N` bytes of the state
+		let mut buf = [0; N];
+		let mut i = 0;
+		while i < N {
+			buf[i] = (h[i / 8] >> (i % 8 * 8)) as u8;
+			i += 1;
+		}
+		buf
+	}
+	
+	/// Loads the message block at `offset` from the message `data` that is prefixed with the
+	/// `0`-padded key block if `key` is not empty
+	const fn load(data: &[u8], key: &[u8], offset: usize) -> [u64; 16] {
+		let key_len = if key.is_empty() { 0 } else { 128 };
+		let mut m = [0; 16];
+		let mut i = 0;
+		while i < 128 {
+			let pos = offset + i;
+			let byte = match pos < key_len {
+				true if pos < key.len() => key[pos],
+				false if pos - key_len < data.len() => data[pos - key_len],
+				_ => 0
+			};
+			m[i / 8] |= (byte as u64) << (i % 8 * 8);
+			i += 1;
+		}
+		m
+	}
+}
\ No newline at end of file
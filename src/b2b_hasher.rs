@@ -0,0 +1,126 @@
+use crate::{ Blake2Error, b2b_impl::B2Impl };
+
+
+digest_type! {
+	/// A [Blake2b](https://blake2.net/blake2.pdf) digest with `1..=64` bytes
+	///
+	/// _Note: Digests compare in constant time (only the lengths are compared in variable time) because
+	/// they may be MAC tags; prefer `Blake2bHasher::verify` to check a MAC tag_
+	Blake2bDigest, 64
+}
+
+
+/// A statically dispatched streaming [Blake2b](https://blake2.net/blake2.pdf) hash or MAC with a
+/// digest length that is selected at runtime
+#[derive(Clone)]
+pub struct Blake2bHasher(B2Impl);
+impl Blake2bHasher {
+	/// Creates a new hash state with the digest length `len` (`1..=64`)
+	pub fn new(len: usize) -> Result<Self, Blake2Error> {
+		check_in!(len, 1..=64);
+		Ok(Self(B2Impl::init_mac(len, &[], &[])))
+	}
+	/// Creates a new MAC state with the digest length `len` (`1..=64`) and `key` (`1..=64` bytes)
+	pub fn new_keyed(len: usize, key: &[u8]) -> Result<Self, Blake2Error> {
+		check_in!(len, 1..=64);
+		check_in!(key.len(), 1..=64);
+		Ok(Self(B2Impl::init_mac(len, key, &[])))
+	}
+	
+	/// Updates the state with `data`
+	pub fn update(&mut self, data: &[u8]) -> &mut Self {
+		self.0.update(data);
+		self
+	}
+	/// Finalizes the state and returns the digest
+	pub fn finalize(mut self) -> Blake2bDigest {
+		let mut digest = Blake2bDigest { bytes: [0; 64], len: self.0.hash_len() };
+		self.0.finish(&mut digest.bytes[..digest.len]);
+		digest
+	}
+	/// Finalizes the state and compares the digest with `tag` in constant time (returns
+	/// `Blake2Error::InvalidTag` if the tag does not match)
+	pub fn verify(mut self, tag: &[u8]) -> Result<(), Blake2Error> {
+		self.0.finish_verify(tag)
+	}
+}
+
+
+/// A statically dispatched streaming [Blake2b](https://blake2.net/blake2.pdf) hash or MAC with the
+/// digest length `N`
+///
+/// The digest length must be in `1..=64`; this is checked at compile time:
+/// ```compile_fail
+/// let hasher = crypto_api_blake2::Blake2bFixed::<65>::new();
+/// ```
+#[derive(Clone)]
+pub struct Blake2bFixed<const N: usize>(B2Impl);
+impl<const N: usize> Blake2bFixed<N> {
+	/// The digest length (fails the compilation if the digest length is invalid)
+	const LEN: usize = {
+		assert!(N >= 1 && N <= 64, "The digest length must be in `1..=64`");
+		N
+	};
+	
+	/// Creates a new hash state
+	pub fn new() -> Self {
+		Self(B2Impl::init_mac(Self::LEN, &[], &[]))
+	}
+	/// Creates a new MAC state with `key` (`1..=64` bytes)
+	pub fn new_keyed(key: &[u8]) -> Result<Self, Blake2Error> {
+		check_in!(key.len(), 1..=64);
+		Ok(Self(B2Impl::init_mac(Self::LEN, key, &[])))
+	}
+	
+	/// Computes the digest of `data`
+	pub fn hash(data: &[u8]) -> [u8; N] {
+		let mut state = Self::new();
+		state.update(data);
+		state.finalize()
+	}
+	/// Computes the MAC of `data` with `key` (`1..=64` bytes)
+	pub fn auth(data: &[u8], key: &[u8]) -> Result<[u8; N], Blake2Error> {
+		let mut state = Self::new_keyed(key)?;
+		state.update(data);
+		Ok(state.finalize())
+	}
+	/// Computes the MAC of `data` with `key` (`1..=64` bytes) and compares it with `tag` in constant
+	/// time (returns `Blake2Error::InvalidTag` if the tag does not match)
+	pub fn verify_auth(tag: &[u8], data: &[u8], key: &[u8]) -> Result<(), Blake2Error> {
+		let mut state = Self::new_keyed(key)?;
+		state.update(data);
+		state.verify(tag)
+	}
+	
+	/// Updates the state with `data`
+	pub fn update(&mut self, data: &[u8]) -> &mut Self {
+		self.0.update(data);
+		self
+	}
+	/// Finalizes the state and returns the digest
+	pub fn finalize(mut self) -> [u8; N] {
+		let mut digest = [0; N];
+		self.0.finish(&mut digest);
+		digest
+	}
+	/// Finalizes the state and compares the digest with `tag` in constant time (returns
+	/// `Blake2Error::InvalidTag` if the tag does not match)
+	pub fn verify(mut self, tag: &[u8]) -> Result<(), Blake2Error> {
+		self.0.finish_verify(tag)
+	}
+}
+impl<const N: usize> Default for Blake2bFixed<N> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+
+/// Blake2b with a 160-bit digest
+pub type Blake2b160 = Blake2bFixed<20>;
+/// Blake2b with a 256-bit digest
+pub type Blake2b256 = Blake2bFixed<32>;
+/// Blake2b with a 384-bit digest
+pub type Blake2b384 = Blake2bFixed<48>;
+/// Blake2b with a 512-bit digest
+pub type Blake2b512 = Blake2bFixed<64>;
\ No newline at end of file
@@ -1,4 +1,5 @@
-use std::{ slice, cmp::min };
+use crate::{ Blake2Error, b2b_backend, ct, wipe::wipe };
+use core::cmp::min;
 
 
 /// Overflowing add
@@ -8,11 +9,11 @@ macro_rules! add {
 }
 
 
-const IV: [u64; 8] = [
+pub const IV: [u64; 8] = [
 	0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
 	0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179
 ];
-const SIGMA: [[u8; 16]; 12] = [
+pub const SIGMA: [[u8; 16]; 12] = [
 	[ 0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15],
 	[14, 10,  4,  8,  9, 15, 13,  6,  1, 12,  0,  2, 11,  7,  5,  3],
 	[11,  8, 12,  0,  5,  2, 15, 13, 10, 14,  3,  6,  7,  1,  9,  4],
@@ -28,136 +29,239 @@ const SIGMA: [[u8; 16]; 12] = [
 ];
 
 
+/// The portable compression function
+///
+/// _Note: This function is `const` so that it can also be used for compile-time hashing; the work
+/// array `v` is passed by the caller so that it can be wiped afterwards_
+pub const fn compress_portable(h: &mut[u64; 8], m: &[u64; 16], t: &[u64; 2], f: &[u64; 2], v: &mut[u64; 16]) {
+	/// G function of compression
+	#[allow(clippy::too_many_arguments)]
+	#[inline(always)]
+	const fn g(r: usize, i: usize, v: &mut[u64; 16], m: &[u64; 16], a: usize, b: usize, c: usize, d: usize) {
+		v[a] = add!(v[a], v[b], m[SIGMA[r][2 * i] as usize]);
+		v[d] = (v[d] ^ v[a]).rotate_right(32);
+		v[c] = add!(v[c], v[d]);
+		v[b] = (v[b] ^ v[c]).rotate_right(24);
+		v[a] = add!(v[a], v[b], m[SIGMA[r][2 * i + 1] as usize]);
+		v[d] = (v[d] ^ v[a]).rotate_right(16);
+		v[c] = add!(v[c], v[d]);
+		v[b] = (v[b] ^ v[c]).rotate_right(63);
+	}
+	/// One compression round
+	#[inline(always)]
+	const fn round(r: usize, v: &mut[u64; 16], m: &[u64; 16]) {
+		g(r, 0, v, m,  0,  4,  8, 12);
+		g(r, 1, v, m,  1,  5,  9, 13);
+		g(r, 2, v, m,  2,  6, 10, 14);
+		g(r, 3, v, m,  3,  7, 11, 15);
+		g(r, 4, v, m,  0,  5, 10, 15);
+		g(r, 5, v, m,  1,  6, 11, 12);
+		g(r, 6, v, m,  2,  7,  8, 13);
+		g(r, 7, v, m,  3,  4,  9, 14);
+	}
+	
+	// Load v
+	*v = [
+		h[0], h[1], h[2], h[3], h[4], h[5], h[6], h[7],
+		IV[0], IV[1], IV[2], IV[3], IV[4] ^ t[0], IV[5] ^ t[1], IV[6] ^ f[0], IV[7] ^ f[1]
+	];
+	
+	// Do rounds and update state (`while` instead of `for` because of `const`)
+	let mut r = 0;
+	while r < 12 {
+		round(r, v, m);
+		r += 1;
+	}
+	let mut i = 0;
+	while i < 8 {
+		h[i] = h[i] ^ v[i] ^ v[i + 8];
+		i += 1;
+	}
+}
+
+
+/// The length of an exported state
+pub const EXPORT_LEN: usize = 248;
+/// The magic bytes of an exported state
+const EXPORT_MAGIC: &[u8; 4] = b"B2bS";
+/// The version of the export format
+///
+/// _Note: Version `2` added the precompressed flag and the final chain values of a precompressed
+/// state; version `1` exports are rejected because their layout is not checked against these fields_
+const EXPORT_VERSION: u8 = 2;
+/// The length of the unkeyed corruption check digest at the end of an exported state
+const EXPORT_CHECK_LEN: usize = 16;
+
+
 /// A Blake2b state with the real implementation
+///
+/// _Note: The state is wiped on drop; the chain value, counter and buffer are also wiped once the
+/// final hash has been computed_
+#[derive(Clone)]
 pub struct B2Impl {
-	h: Vec<u64>, // 8
-	t: Vec<u64>, // 2
-	f: Vec<u64>, // 2
+	h: [u64; 8],
+	t: [u64; 2],
+	f: [u64; 2],
+	
+	buf: [u8; 128],
+	buf_len: usize,
+	hash_len: usize,
+	last_node: bool,
 	
-	buf: Vec<u8>, // 128
-	hash_len: usize
+	/// The final chain values without and with the last node flag if the last block has been
+	/// compressed ahead (see `precompress`) and no data has been absorbed since then
+	empty: [[u64; 8]; 2],
+	precompressed: bool
 }
 impl B2Impl {
-	/// Initializes the state as hash with `out_len`
-	pub fn init(out_len: usize) -> Self {
-		Self::init_kdf(out_len, &[], &[], &[])
+	/// Initializes the state as hash with `out_len` and the personalization string `personal`
+	#[cfg(feature = "std")]
+	pub fn init(out_len: usize, personal: &[u8]) -> Self {
+		Self::init_kdf(out_len, &[], &[], personal)
 	}
-	/// Initializes the state as MAC with `out_len` and `key`
-	pub fn init_mac(out_len: usize, key: &[u8]) -> Self {
-		Self::init_kdf(out_len, key, &[], &[])
+	/// Initializes the state as MAC with `out_len`, `key` and the personalization string `personal`
+	pub fn init_mac(out_len: usize, key: &[u8], personal: &[u8]) -> Self {
+		Self::init_kdf(out_len, key, &[], personal)
 	}
 	/// Initializes the state as KDF with `out_len`, `base_key`, `salt` and `info`
+	///
+	/// _Note: `info` is stored in the personalization field of the parameter block_
 	pub fn init_kdf(out_len: usize, base_key: &[u8], salt: &[u8], info: &[u8]) -> Self {
-		// Validate the output length
-		assert!(out_len <= 64, "Output length is too large");
-		assert!(base_key.len() <= 64, "Key is too large");
+		// Validate the salt and info length
 		assert!(salt.len() <= 16, "Salt is too large");
 		assert!(info.len() <= 16, "Info is too large");
 		
+		// Create parameters
+		let mut p = [0; 64];
+		p[0] = out_len as u8;
+		p[1] = base_key.len() as u8;
+		p[2] = 1; // Fan-out
+		p[3] = 1; // Depth
+		
+		// Copy salt and info (= personalization)
+		p[32 .. 32 + salt.len()].copy_from_slice(salt);
+		p[48 .. 48 + info.len()].copy_from_slice(info);
+		
+		Self::init_params(&p, out_len, base_key)
+	}
+	/// Initializes the state with the raw parameter block `params`, `out_len` and `key`
+	///
+	/// _Note: `out_len` may differ from the digest length in `params` (e.g. for inner tree nodes)_
+	pub fn init_params(params: &[u8; 64], out_len: usize, key: &[u8]) -> Self {
+		// Validate the output and key length
+		assert!(out_len <= 64, "Output length is too large");
+		assert!(key.len() <= 64, "Key is too large");
+		
 		// Create Blake2b instance
 		let mut b2 = Self {
-			h: vec![0; 8], t: vec![0; 2], f: vec![0; 2],
-			buf: Vec::with_capacity(128),
-			hash_len: out_len
+			h: [0; 8], t: [0; 2], f: [0; 2],
+			buf: [0; 128], buf_len: 0,
+			hash_len: out_len, last_node: false,
+			empty: [[0; 8]; 2], precompressed: false
 		};
 		
-		// Create parameters
-		{
-			// Cast the memory behind the hash state to an `u8` slice
-			let p =
-				unsafe{ slice::from_raw_parts_mut(b2.h.as_mut_ptr() as *mut u8, 64) };
-			
-			// Set parameters
-			p[0] = out_len as u8;
-			p[1] = base_key.len() as u8;
-			p[2] = 1; // Fan-out
-			p[3] = 1; // Depth
-			
-			// Copy salt and info
-			p[32 .. 32 + salt.len()].copy_from_slice(salt);
-			p[48 .. 48 + info.len()].copy_from_slice(info);
-		}
-		
 		// Xor the parameters with the IV into `b2.h`
-		for i in 0..8 { b2.h[i] ^= IV[i] }
+		for ((h, iv), p) in b2.h.iter_mut().zip(IV.iter()).zip(params.chunks(8)) {
+			let mut num = [0; 8];
+			num.copy_from_slice(p);
+			*h = iv ^ u64::from_le_bytes(num);
+		}
 		
 		// Hash the key as `0`-padded 128 byte block
-		if !base_key.is_empty() {
-			b2.update(base_key);
+		if !key.is_empty() {
+			b2.update(key);
 			
-			let pad_len = 128 - base_key.len();
+			let pad_len = 128 - key.len();
 			b2.update(&[0; 128][..pad_len]);
 		}
 		
 		b2
 	}
+	/// Initializes the state as tree node with the shared parameter block `params`, the node's
+	/// `node_offset` and `node_depth`, `out_len` and `key`
+	#[cfg(feature = "std")]
+	pub fn init_node(params: &[u8; 64], node_offset: u64, node_depth: u8, out_len: usize, key: &[u8]) -> Self {
+		let mut p = *params;
+		p[8..16].copy_from_slice(&node_offset.to_le_bytes());
+		p[16] = node_depth;
+		Self::init_params(&p, out_len, key)
+	}
 	
-	/// The compression function
-	fn compress(&mut self) {
-		/// G function of compression
-		fn g(r: usize, i: usize, v: &mut[u64], m: &mut[u64], a: usize, b: usize, c: usize, d: usize) {
-			v[a] = add!(v[a], v[b], m[SIGMA[r][2 * i + 0] as usize]);
-			v[d] = (v[d] ^ v[a]).rotate_right(32);
-			v[c] = add!(v[c], v[d]);
-			v[b] = (v[b] ^ v[c]).rotate_right(24);
-			v[a] = add!(v[a], v[b], m[SIGMA[r][2 * i + 1] as usize]);
-			v[d] = (v[d] ^ v[a]).rotate_right(16);
-			v[c] = add!(v[c], v[d]);
-			v[b] = (v[b] ^ v[c]).rotate_right(63);
-		}
-		/// One compression round
-		fn round(r: usize, v: &mut[u64], m: &mut[u64]) {
-			g(r, 0, v, m,  0,  4,  8, 12);
-			g(r, 1, v, m,  1,  5,  9, 13);
-			g(r, 2, v, m,  2,  6, 10, 14);
-			g(r, 3, v, m,  3,  7, 11, 15);
-			g(r, 4, v, m,  0,  5, 10, 15);
-			g(r, 5, v, m,  1,  6, 11, 12);
-			g(r, 6, v, m,  2,  7,  8, 13);
-			g(r, 7, v, m,  3,  4,  9, 14);
-		}
-		
-		// Load m
-		let mut m = vec![0; 16];
+	/// Loads the 128 byte message `block`
+	fn load(block: &[u8]) -> [u64; 16] {
+		let mut m = [0; 16];
 		for i in 0..16 {
 			let mut num = [0; 8];
-			num.copy_from_slice(&self.buf[i * 8 .. (i + 1) * 8]);
+			num.copy_from_slice(&block[i * 8 .. (i + 1) * 8]);
 			m[i] = u64::from_le_bytes(num);
 		}
+		m
+	}
+	/// Increments the counter by `len`, compresses the message block `m` using the current backend and
+	/// wipes `m`
+	fn compress(&mut self, len: u64, m: &mut[u64; 16]) {
+		self.t[0] = add!(self.t[0], len);
+		if self.t[0] < len { self.t[1] += 1 }
+		b2b_backend::compress(&mut self.h, m, &self.t, &self.f);
+		wipe(m);
+	}
+	
+	/// Compresses the buffered block ahead if it is complete (e.g. the `0`-padded key block of a
+	/// fresh keyed state); does nothing otherwise
+	///
+	/// _Note: This is used for the initial state of `reset`, so that a reset does neither compress
+	/// the key block again nor keep a copy of it; the final chain values for the case that no further
+	/// data is absorbed are computed ahead instead_
+	pub fn precompress(&mut self) {
+		if self.buf_len != 128 || self.is_finished() { return }
 		
-		// Load v
-		let mut v = vec![0; 16];
-		v[ 0.. 8].copy_from_slice(&self.h[..8]);
-		v[ 8..12].copy_from_slice(&IV[0..4]);
-		v[12] = IV[4] ^ self.t[0];
-		v[13] = IV[5] ^ self.t[1];
-		v[14] = IV[6] ^ self.f[0];
-		v[15] = IV[7] ^ self.f[1];
+		// Compute the final chain values without and with the last node flag
+		for last_node in [false, true].iter().copied() {
+			let mut last = self.clone();
+			last.f = [u64::MAX, if last_node { u64::MAX } else { 0 }];
+			last.compress(128, &mut Self::load(&last.buf));
+			self.empty[last_node as usize] = last.h;
+		}
 		
-		// Do rounds and update state
-		for r in 0..12 { round(r, &mut v, &mut m) }
-		for i in 0..8 { self.h[i] = self.h[i] ^ v[i] ^ v[i + 8] }
+		// Compress the buffered block
+		self.compress(128, &mut Self::load(&self.buf));
+		wipe(&mut self.buf);
+		self.buf_len = 0;
+		self.precompressed = true;
 	}
 	
 	/// Updates the state with `data`
+	///
+	/// _Note: Full blocks are compressed directly from `data`; only the last (possibly final) block
+	/// is buffered_
 	pub fn update(&mut self, mut data: &[u8]) -> &mut Self {
+		// Discard the precomputed final chain values once data is absorbed
+		if self.precompressed && !data.is_empty() {
+			wipe(&mut self.empty);
+			self.precompressed = false;
+		}
+		
 		// Process data
 		while !data.is_empty() {
-			// Fill buffer
-			let to_copy = min(128 - self.buf.len(), data.len());
-			self.buf.extend_from_slice(&data[..to_copy]);
-			data = &data[to_copy..];
+			// Compress the buffered block now that we know that it is not the last block
+			if self.buf_len == 128 {
+				self.compress(128, &mut Self::load(&self.buf));
+				self.buf_len = 0;
+			}
 			
-			// Process full block if possible and we are not the last block
-			if self.buf.len() == 128 && !data.is_empty() {
-				// Increment counter
-				self.t[0] = add!(self.t[0], 128);
-				if self.t[0] < 128 { self.t[1] += 1 }
-				
-				// Compress block and clear buffer
-				self.compress();
-				self.buf.clear();
+			// Compress all full blocks except for the last one directly from `data`
+			if self.buf_len == 0 {
+				while data.len() > 128 {
+					self.compress(128, &mut Self::load(&data[..128]));
+					data = &data[128..];
+				}
 			}
+			
+			// Buffer the remaining data
+			let to_copy = min(128 - self.buf_len, data.len());
+			self.buf[self.buf_len .. self.buf_len + to_copy].copy_from_slice(&data[..to_copy]);
+			self.buf_len += to_copy;
+			data = &data[to_copy..];
 		}
 		self
 	}
@@ -168,27 +272,163 @@ impl B2Impl {
 		assert_eq!(buf.len(), self.hash_len, "Invalid buffer length");
 		assert_eq!(self.f[0], 0, "Final hash has already been computed");
 		
-		// Increment counter
-		self.t[0] = add!(self.t[0], self.buf.len() as u64);
-		if self.t[0] < self.buf.len() as u64 { self.t[1] += 1 }
-		
-		// Set final block
-		self.f[0] = u64::max_value();
+		// Set final block and last node flags
+		self.f[0] = u64::MAX;
+		if self.last_node { self.f[1] = u64::MAX }
 		
-		// `0`-pad the buffer to a complete block length
-		self.buf.resize(128, 0);
-		self.compress();
+		// `0`-pad the buffer to a complete block length and compress it (unless the last block has
+		// already been compressed ahead)
+		match self.precompressed {
+			true => self.h = self.empty[self.last_node as usize],
+			false => {
+				self.buf[self.buf_len..].iter_mut().for_each(|b| *b = 0);
+				self.compress(self.buf_len as u64, &mut Self::load(&self.buf));
+			}
+		}
 		
 		// Store the entire 512-bit hash in `out`
-		let mut out = vec![0; 64];
+		let mut out = [0; 64];
 		for i in 0..8 {
 			let num = self.h[i].to_le_bytes();
 			out[i * 8 .. (i + 1) * 8].copy_from_slice(&num);
 		}
-		buf.copy_from_slice(&out[..buf.len()])
+		buf.copy_from_slice(&out[..buf.len()]);
+		
+		// Wipe everything except for the flags which mark the state as finished
+		wipe(&mut out);
+		wipe(&mut self.h);
+		wipe(&mut self.t);
+		wipe(&mut self.buf);
+		wipe(&mut self.buf_len);
+		wipe(&mut self.empty);
+	}
+	
+	/// Finalizes the state and compares the digest with `tag` in constant time
+	pub fn finish_verify(&mut self, tag: &[u8]) -> Result<(), Blake2Error> {
+		let mut digest = [0; 64];
+		self.finish(&mut digest[..self.hash_len]);
+		let is_valid = ct::eq(&digest[..self.hash_len], tag);
+		wipe(&mut digest);
+		
+		match is_valid {
+			true => Ok(()),
+			false => Err(Blake2Error::InvalidTag)
+		}
+	}
+	
+	/// Serializes the state into the versioned export format
+	///
+	/// Layout: magic `B2bS` (4 bytes), version `2` (1 byte), digest length (1 byte), buffer length
+	/// (1 byte), flags (1 byte; bit `0` is the last node flag, bit `1` marks a precompressed state),
+	/// `h` (64 bytes), `t` (16 bytes), `f` (16 bytes), the `0`-padded buffer or the final chain
+	/// values of a precompressed state (128 bytes) and a 16 byte corruption check (an unkeyed Blake2b
+	/// digest over the previous bytes); all integers are little endian
+	///
+	/// _Note: The corruption check only detects accidental corruption; it is not a MAC, so anyone who
+	/// can modify an exported state can also recompute it_
+	pub fn export(&self) -> [u8; EXPORT_LEN] {
+		assert!(!self.is_finished(), "Final hash has already been computed");
+		
+		// Serialize the fields
+		let mut bytes = [0; EXPORT_LEN];
+		bytes[0..4].copy_from_slice(EXPORT_MAGIC);
+		bytes[4] = EXPORT_VERSION;
+		bytes[5] = self.hash_len as u8;
+		bytes[6] = self.buf_len as u8;
+		bytes[7] = self.last_node as u8 | (self.precompressed as u8) << 1;
+		for (chunk, word) in bytes[8..104].chunks_mut(8).zip(self.h.iter().chain(&self.t).chain(&self.f)) {
+			chunk.copy_from_slice(&word.to_le_bytes());
+		}
+		match self.precompressed {
+			true => for (chunk, word) in bytes[104..232].chunks_mut(8).zip(self.empty.iter().flatten()) {
+				chunk.copy_from_slice(&word.to_le_bytes());
+			},
+			false => bytes[104 .. 104 + self.buf_len].copy_from_slice(&self.buf[..self.buf_len])
+		}
+		
+		// Append the corruption check
+		let (fields, check) = bytes.split_at_mut(EXPORT_LEN - EXPORT_CHECK_LEN);
+		Self::init_mac(EXPORT_CHECK_LEN, &[], &[]).update(fields).finish(check);
+		bytes
+	}
+	/// Deserializes and validates a state that has been serialized using `export`
+	pub fn import(bytes: &[u8]) -> Result<Self, Blake2Error> {
+		// Validate the format and the corruption check
+		check_eq!(bytes.len(), EXPORT_LEN);
+		if &bytes[0..4] != EXPORT_MAGIC {
+			Err(Blake2Error::ApiMisuse("The state has an invalid format"))?
+		}
+		if bytes[4] != EXPORT_VERSION {
+			Err(Blake2Error::ApiMisuse("The state version is not supported"))?
+		}
+		let mut check = [0; EXPORT_CHECK_LEN];
+		Self::init_mac(EXPORT_CHECK_LEN, &[], &[]).update(&bytes[..EXPORT_LEN - EXPORT_CHECK_LEN]).finish(&mut check);
+		if check != bytes[EXPORT_LEN - EXPORT_CHECK_LEN..] {
+			Err(Blake2Error::ApiMisuse("The state is corrupted"))?
+		}
+		
+		// Deserialize the fields
+		let (hash_len, buf_len, flags) = (bytes[5] as usize, bytes[6] as usize, bytes[7]);
+		let mut words = [0; 12];
+		for (word, chunk) in words.iter_mut().zip(bytes[8..104].chunks(8)) {
+			let mut num = [0; 8];
+			num.copy_from_slice(chunk);
+			*word = u64::from_le_bytes(num);
+		}
+		let mut b2 = Self {
+			h: [0; 8], t: [words[8], words[9]], f: [words[10], words[11]],
+			buf: [0; 128], buf_len, hash_len, last_node: flags & 1 == 1,
+			empty: [[0; 8]; 2], precompressed: flags & 2 == 2
+		};
+		b2.h.copy_from_slice(&words[..8]);
+		wipe(&mut words);
+		
+		// Validate the fields (the buffer may only be empty if nothing has been compressed yet or if
+		// the last block has been compressed ahead)
+		let is_valid = (1..=64).contains(&hash_len) && buf_len <= 128 && flags <= 3
+			&& b2.f == [0, 0] && b2.t[0].is_multiple_of(128)
+			&& match b2.precompressed {
+				true => buf_len == 0 && b2.t != [0, 0],
+				false => (b2.t == [0, 0] || buf_len > 0) && bytes[104 + buf_len .. 232].iter().all(|b| *b == 0)
+			};
+		if !is_valid {
+			Err(Blake2Error::ApiMisuse("The state is invalid"))?
+		}
+		match b2.precompressed {
+			true => for (word, chunk) in b2.empty.iter_mut().flatten().zip(bytes[104..232].chunks(8)) {
+				let mut num = [0; 8];
+				num.copy_from_slice(chunk);
+				*word = u64::from_le_bytes(num);
+			},
+			false => b2.buf[..buf_len].copy_from_slice(&bytes[104 .. 104 + buf_len])
+		}
+		Ok(b2)
+	}
+	
+	/// Marks the state as last node of its tree level
+	pub fn set_last_node(&mut self, last_node: bool) -> &mut Self {
+		self.last_node = last_node;
+		self
 	}
 	
+	/// Whether the final hash has already been computed or not
+	pub fn is_finished(&self) -> bool {
+		self.f[0] != 0
+	}
 	pub fn hash_len(&self) -> usize {
 		self.hash_len
 	}
+}
+impl Drop for B2Impl {
+	fn drop(&mut self) {
+		wipe(&mut self.h);
+		wipe(&mut self.t);
+		wipe(&mut self.f);
+		wipe(&mut self.buf);
+		wipe(&mut self.buf_len);
+		wipe(&mut self.hash_len);
+		wipe(&mut self.last_node);
+		wipe(&mut self.empty);
+		wipe(&mut self.precompressed);
+	}
 }
\ No newline at end of file
@@ -0,0 +1,83 @@
+use crate::{ Blake2Error, b2b_impl::B2Impl, ct, wipe::wipe };
+use core::{ fmt::{ self, Debug, Formatter }, ops::Deref };
+#[cfg(feature = "std")]
+use crypto_api::rng::SecureRng;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+
+/// The personalization string that separates pre-hashed keys from all other Blake2b hashes
+const PREHASH_PERSONAL: &[u8; 16] = b"blake2b-long-key";
+
+
+/// A secret [Blake2b](https://blake2.net/blake2.pdf) key with `1..=64` bytes
+///
+/// The key is wiped on drop, its `Debug` output only shows the length and it is compared in constant
+/// time. It dereferences to `&[u8]`, so `&key` can be passed to all keyed APIs.
+#[derive(Clone)]
+pub struct Blake2bKey {
+	bytes: [u8; 64],
+	len: usize
+}
+impl Blake2bKey {
+	/// Copies `key` (`1..=64` bytes)
+	pub fn new(key: &[u8]) -> Result<Self, Blake2Error> {
+		check_in!(key.len(), 1..=64);
+		
+		let mut bytes = [0; 64];
+		bytes[..key.len()].copy_from_slice(key);
+		Ok(Self { bytes, len: key.len() })
+	}
+	/// Derives a 64 byte key from `key` with an arbitrary length by hashing it with Blake2b-512 and
+	/// the personalization string `blake2b-long-key`
+	///
+	/// _Note: This is a distinct keyed mode: the derived key differs from `key` even if `key` is not
+	/// longer than 64 bytes, so both parties must use the same mode_
+	pub fn prehash(key: &[u8]) -> Self {
+		let mut prehashed = Self { bytes: [0; 64], len: 64 };
+		B2Impl::init_mac(64, &[], PREHASH_PERSONAL).update(key).finish(&mut prehashed.bytes);
+		prehashed
+	}
+	/// Generates a new random key with `len` bytes (`1..=64`) using `rng`
+	#[cfg(feature = "std")]
+	pub fn generate(len: usize, rng: &mut dyn SecureRng) -> Result<Self, Box<dyn Error + 'static>> {
+		check_in!(len, 1..=64);
+		
+		let mut key = Self { bytes: [0; 64], len };
+		rng.random(&mut key.bytes[..len])?;
+		Ok(key)
+	}
+	
+	/// The key bytes
+	pub fn as_slice(&self) -> &[u8] {
+		&self.bytes[..self.len]
+	}
+}
+impl Deref for Blake2bKey {
+	type Target = [u8];
+	fn deref(&self) -> &Self::Target {
+		self.as_slice()
+	}
+}
+impl AsRef<[u8]> for Blake2bKey {
+	fn as_ref(&self) -> &[u8] {
+		self.as_slice()
+	}
+}
+impl PartialEq for Blake2bKey {
+	fn eq(&self, other: &Self) -> bool {
+		ct::eq(self.as_slice(), other.as_slice())
+	}
+}
+impl Eq for Blake2bKey {}
+impl Debug for Blake2bKey {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "Blake2bKey(<{} bytes redacted>)", self.len)
+	}
+}
+impl Drop for Blake2bKey {
+	fn drop(&mut self) {
+		wipe(&mut self.bytes);
+		wipe(&mut self.len);
+	}
+}
\ No newline at end of file
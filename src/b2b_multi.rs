@@ -0,0 +1,142 @@
+use crate::{ Blake2Error, b2b_backend::{ self, Blake2bBackend }, b2b_impl::IV, wipe::wipe };
+use core::cmp::{ max, min };
+
+
+/// A single message for `Blake2bMulti` that computes the (optionally keyed) digest of `input`
+/// into `buf`
+pub struct Blake2bJob<'a> {
+	buf: &'a mut[u8],
+	input: &'a [u8],
+	key: &'a [u8]
+}
+impl<'a> Blake2bJob<'a> {
+	/// Creates a new hash job with the digest length `buf.len()` (`1..=64`)
+	pub fn new(buf: &'a mut[u8], input: &'a [u8]) -> Self {
+		Self { buf, input, key: &[] }
+	}
+	/// Creates a new MAC job with the digest length `buf.len()` (`1..=64`) and `key` (`0..=64`
+	/// bytes; an empty key disables keyed hashing)
+	pub fn new_keyed(buf: &'a mut[u8], input: &'a [u8], key: &'a [u8]) -> Self {
+		Self { buf, input, key }
+	}
+	
+	/// The total length of the message (including the `0`-padded key block)
+	fn len(&self) -> u64 {
+		let key_len = if self.key.is_empty() { 0 } else { 128 };
+		key_len + self.input.len() as u64
+	}
+	/// The amount of blocks to compress (at least `1`)
+	fn blocks(&self) -> u64 {
+		max(self.len().div_ceil(128), 1)
+	}
+	
+	/// The initial state
+	fn init(&self) -> [u64; 8] {
+		// Xor the parameter block (digest length, key length, fan-out and depth) with the IV
+		let mut h = IV;
+		h[0] ^= 0x0101_0000 | (self.key.len() as u64) << 8 | self.buf.len() as u64;
+		h
+	}
+	/// Loads the `i`-th message block into `m` and sets the counter `t` and flags `f` accordingly
+	fn load(&self, i: u64, m: &mut[u64; 16], t: &mut[u64; 2], f: &mut[u64; 2]) {
+		// Copy the block (the first block is the key block if the job is keyed)
+		let mut block = [0; 128];
+		match (i, self.key.is_empty()) {
+			(0, false) => block[..self.key.len()].copy_from_slice(self.key),
+			(i, keyless) => {
+				let offset = (if keyless { i } else { i - 1 } as usize) * 128;
+				let data = &self.input[offset..min(offset + 128, self.input.len())];
+				block[..data.len()].copy_from_slice(data);
+			}
+		}
+		for (m, chunk) in m.iter_mut().zip(block.chunks(8)) {
+			let mut num = [0; 8];
+			num.copy_from_slice(chunk);
+			*m = u64::from_le_bytes(num);
+		}
+		wipe(&mut block);
+		
+		// Set the counter and the final block flag
+		let is_last = i + 1 == self.blocks();
+		*t = [if is_last { self.len() } else { (i + 1) * 128 }, 0];
+		*f = [if is_last { u64::MAX } else { 0 }, 0];
+	}
+	/// Stores the final state `h` as digest
+	fn store(&mut self, h: &[u64; 8]) {
+		let mut out = [0; 64];
+		for (out, h) in out.chunks_mut(8).zip(h.iter()) { out.copy_from_slice(&h.to_le_bytes()) }
+		let buf_len = self.buf.len();
+		self.buf.copy_from_slice(&out[..buf_len]);
+		wipe(&mut out);
+	}
+}
+
+
+/// A multi-buffer [Blake2b](https://blake2.net/blake2.pdf) implementation that hashes many
+/// independent messages at once by interleaving 4 (AVX2) or 8 (AVX-512) states across the SIMD
+/// lanes
+///
+/// The digests are identical to those of `VarlenHash::varlen_hash` (or `VarlenMac::varlen_auth` for
+/// keyed jobs). Without a multi-lane SIMD backend, the busy states are compressed one after another.
+pub struct Blake2bMulti;
+impl Blake2bMulti {
+	/// Computes the digests of all `jobs`
+	pub fn hash(jobs: &mut[Blake2bJob]) -> Result<(), Blake2Error> {
+		// Validate all jobs before hashing anything
+		for job in jobs.iter() {
+			check_in!(job.buf.len(), 1..=64);
+			check_in!(job.key.len(), 0..=64);
+		}
+		
+		match Blake2bBackend::current() {
+			Blake2bBackend::Avx512 => Self::hash_lanes::<8>(jobs),
+			_ => Self::hash_lanes::<4>(jobs)
+		}
+		Ok(())
+	}
+	
+	/// Computes the digests of all `jobs` using `LANES` interleaved states; each lane picks up the
+	/// next job as soon as its current job is finished
+	///
+	/// _Note: The chain values, message blocks, counters and flags of all lanes are wiped once all jobs
+	/// are finished_
+	fn hash_lanes<const LANES: usize>(jobs: &mut[Blake2bJob]) {
+		let (mut h, mut m) = ([[0; 8]; LANES], [[0; 16]; LANES]);
+		let (mut t, mut f) = ([[0; 2]; LANES], [[0; 2]; LANES]);
+		let (mut lanes, mut next): ([Option<(usize, u64)>; LANES], usize) = ([None; LANES], 0);
+		loop {
+			// Assign the next jobs to the idle lanes
+			for (lane, h) in lanes.iter_mut().zip(h.iter_mut()) {
+				if lane.is_none() && next < jobs.len() {
+					*h = jobs[next].init();
+					*lane = Some((next, 0));
+					next += 1;
+				}
+			}
+			if lanes.iter().all(Option::is_none) { break }
+			
+			// Compress the next block of each busy lane (the SIMD backends also compress the idle lanes,
+			// but their results are ignored)
+			for (i, lane) in lanes.iter().enumerate() {
+				if let Some((job, block)) = *lane { jobs[job].load(block, &mut m[i], &mut t[i], &mut f[i]) }
+			}
+			let active = lanes.map(|lane| lane.is_some());
+			b2b_backend::compress_many(&mut h, &m, &t, &f, &active);
+			
+			// Advance the busy lanes and store the finished digests
+			for (lane, h) in lanes.iter_mut().zip(h.iter()) {
+				if let Some((job, block)) = lane.as_mut() {
+					*block += 1;
+					if *block == jobs[*job].blocks() {
+						jobs[*job].store(h);
+						*lane = None;
+					}
+				}
+			}
+		}
+		wipe(&mut h);
+		wipe(&mut m);
+		wipe(&mut t);
+		wipe(&mut f);
+	}
+}
\ No newline at end of file
@@ -0,0 +1,189 @@
+use crate::{ Blake2Error, b2b_impl::B2Impl, b2b_state::Blake2bState, wipe::wipe };
+#[cfg(feature = "std")]
+use crate::b2b_tree::Blake2bTree;
+use core::cmp::min;
+
+
+/// A fixed-size byte buffer that remembers the length of oversized inputs for validation
+#[derive(Clone, Copy)]
+struct Bytes<const N: usize> {
+	data: [u8; N],
+	len: usize
+}
+impl<const N: usize> Bytes<N> {
+	/// Copies up to `N` bytes of `bytes`
+	fn new(bytes: &[u8]) -> Self {
+		let mut data = [0; N];
+		let to_copy = min(bytes.len(), N);
+		data[..to_copy].copy_from_slice(&bytes[..to_copy]);
+		Self { data, len: bytes.len() }
+	}
+	/// The length of the input (which may exceed `N`)
+	fn len(&self) -> usize {
+		self.len
+	}
+	/// The stored bytes
+	fn as_slice(&self) -> &[u8] {
+		&self.data[..min(self.len, N)]
+	}
+}
+
+
+/// A builder for the full [Blake2b](https://blake2.net/blake2.pdf) parameter block
+///
+/// The defaults describe a sequential, unkeyed 512-bit hash (fan-out = 1, depth = 1). All
+/// parameters are validated once the state is created using `to_state` or `to_tree`. The key is
+/// wiped on drop.
+#[derive(Clone)]
+pub struct Blake2bParams {
+	hash_len: usize,
+	key: Bytes<64>,
+	salt: Bytes<16>,
+	personal: Bytes<16>,
+	fanout: u8,
+	depth: u8,
+	leaf_len: u32,
+	node_offset: u64,
+	node_depth: u8,
+	inner_len: usize,
+	last_node: bool
+}
+impl Blake2bParams {
+	/// Creates a new parameter builder with the sequential-mode defaults
+	pub fn new() -> Self {
+		Self {
+			hash_len: 64, key: Bytes::new(&[]), salt: Bytes::new(&[]), personal: Bytes::new(&[]),
+			fanout: 1, depth: 1, leaf_len: 0, node_offset: 0, node_depth: 0, inner_len: 0,
+			last_node: false
+		}
+	}
+	
+	/// Sets the digest length (`1..=64`)
+	pub fn hash_len(&mut self, hash_len: usize) -> &mut Self {
+		self.hash_len = hash_len;
+		self
+	}
+	/// Sets the key (`0..=64` bytes; an empty key disables keyed hashing)
+	pub fn key(&mut self, key: &[u8]) -> &mut Self {
+		self.key = Bytes::new(key);
+		self
+	}
+	/// Sets the salt (`0..=16` bytes)
+	pub fn salt(&mut self, salt: &[u8]) -> &mut Self {
+		self.salt = Bytes::new(salt);
+		self
+	}
+	/// Sets the personalization string (`0..=16` bytes)
+	pub fn personal(&mut self, personal: &[u8]) -> &mut Self {
+		self.personal = Bytes::new(personal);
+		self
+	}
+	/// Sets the fan-out (`0` means unlimited)
+	pub fn fanout(&mut self, fanout: u8) -> &mut Self {
+		self.fanout = fanout;
+		self
+	}
+	/// Sets the maximal depth (`1..=255`; `255` means unlimited)
+	pub fn depth(&mut self, depth: u8) -> &mut Self {
+		self.depth = depth;
+		self
+	}
+	/// Sets the maximal leaf length in bytes (`0` means unlimited; must be `0` in sequential mode)
+	pub fn leaf_len(&mut self, leaf_len: u32) -> &mut Self {
+		self.leaf_len = leaf_len;
+		self
+	}
+	/// Sets the offset of the node within its tree level
+	pub fn node_offset(&mut self, node_offset: u64) -> &mut Self {
+		self.node_offset = node_offset;
+		self
+	}
+	/// Sets the depth of the node (`0` for leaves or in sequential mode)
+	pub fn node_depth(&mut self, node_depth: u8) -> &mut Self {
+		self.node_depth = node_depth;
+		self
+	}
+	/// Sets the inner hash length (`0` in sequential mode, `1..=64` in tree mode)
+	pub fn inner_len(&mut self, inner_len: usize) -> &mut Self {
+		self.inner_len = inner_len;
+		self
+	}
+	/// Marks the node as the last node of its tree level
+	pub fn last_node(&mut self, last_node: bool) -> &mut Self {
+		self.last_node = last_node;
+		self
+	}
+	
+	/// Validates the parameters and creates a new streaming state
+	pub fn to_state(&self) -> Result<Blake2bState, Blake2Error> {
+		self.validate()?;
+		let mut state = B2Impl::init_params(&self.to_bytes(), self.hash_len, self.key.as_slice());
+		state.set_last_node(self.last_node);
+		Ok(Blake2bState::new(state))
+	}
+	#[cfg(feature = "std")]
+	/// Validates the parameters and creates a new incremental tree hash builder
+	///
+	/// _Note: The node offset, node depth and last node flag are managed by the builder and must not
+	/// be set; the depth must be at least `2`._
+	pub fn to_tree(&self) -> Result<Blake2bTree, Blake2Error> {
+		let depth = self.depth;
+		check_in!(depth, 2..=255);
+		if self.node_offset != 0 || self.node_depth != 0 || self.last_node {
+			Err(Blake2Error::ApiMisuse("Node parameters are set for a tree builder"))?
+		}
+		self.validate()?;
+		
+		Ok(Blake2bTree::new(self.to_bytes(), self.key.as_slice()))
+	}
+	
+	/// Validates the parameters
+	fn validate(&self) -> Result<(), Blake2Error> {
+		// Validate the lengths
+		let (hash_len, inner_len) = (self.hash_len, self.inner_len);
+		check_in!(hash_len, 1..=64);
+		check_in!(self.key.len(), 0..=64);
+		check_in!(self.salt.len(), 0..=16);
+		check_in!(self.personal.len(), 0..=16);
+		check_in!(inner_len, 0..=64);
+		
+		// Validate the tree parameters
+		let (depth, node_depth) = (self.depth, self.node_depth);
+		check_in!(depth, 1..=255);
+		check_in!(node_depth, 0..=depth - 1);
+		match depth {
+			1 if self.fanout != 1 || self.leaf_len != 0 || self.node_offset != 0 || inner_len != 0 =>
+				Err(Blake2Error::ApiMisuse("Tree parameters are set in sequential mode"))?,
+			1 => (),
+			_ => check_in!(inner_len, 1..=64)
+		}
+		Ok(())
+	}
+	
+	/// Serializes the parameters into a raw parameter block
+	pub(crate) fn to_bytes(&self) -> [u8; 64] {
+		let mut p = [0; 64];
+		p[0] = self.hash_len as u8;
+		p[1] = self.key.len() as u8;
+		p[2] = self.fanout;
+		p[3] = self.depth;
+		p[4..8].copy_from_slice(&self.leaf_len.to_le_bytes());
+		p[8..16].copy_from_slice(&self.node_offset.to_le_bytes());
+		p[16] = self.node_depth;
+		p[17] = self.inner_len as u8;
+		p[32 .. 32 + self.salt.len()].copy_from_slice(self.salt.as_slice());
+		p[48 .. 48 + self.personal.len()].copy_from_slice(self.personal.as_slice());
+		p
+	}
+}
+impl Default for Blake2bParams {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+impl Drop for Blake2bParams {
+	fn drop(&mut self) {
+		wipe(&mut self.key.data);
+		wipe(&mut self.key.len);
+	}
+}
\ No newline at end of file
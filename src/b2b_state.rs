@@ -0,0 +1,86 @@
+use crate::{ Blake2Error, b2b_impl::{ self, B2Impl } };
+
+
+/// A streaming [Blake2b](https://blake2.net/blake2.pdf) state created by `Blake2bParams`
+#[derive(Clone)]
+pub struct Blake2bState {
+	state: B2Impl,
+	initial: B2Impl
+}
+impl Blake2bState {
+	/// Wraps `state` and keeps a copy of it as initial state
+	pub(crate) fn new(state: B2Impl) -> Self {
+		Self { initial: state.clone(), state }
+	}
+	
+	/// Updates the state with `data`
+	pub fn update(&mut self, data: &[u8]) -> &mut Self {
+		self.state.update(data);
+		self
+	}
+	/// Marks the state as the last node of its tree level (sets the last node flag `f[1]` on
+	/// finalization)
+	///
+	/// _Note: This can be called at any time before `finish`, e.g. once it is known that no further
+	/// node follows on the same level_
+	pub fn set_last_node(&mut self, last_node: bool) -> &mut Self {
+		self.state.set_last_node(last_node);
+		self
+	}
+	/// Finalizes the state and computes the digest into `buf`
+	pub fn finish(&mut self, buf: &mut[u8]) -> Result<usize, Blake2Error> {
+		if self.state.is_finished() {
+			Err(Blake2Error::ApiMisuse("The state has already been finalized"))?
+		}
+		check_eq!(buf.len(), self.state.hash_len());
+		
+		self.state.finish(buf);
+		Ok(buf.len())
+	}
+	/// Finalizes the state and compares the digest with `tag` in constant time (returns
+	/// `Blake2Error::InvalidTag` if the tag does not match)
+	pub fn finish_verify(&mut self, tag: &[u8]) -> Result<(), Blake2Error> {
+		if self.state.is_finished() {
+			Err(Blake2Error::ApiMisuse("The state has already been finalized"))?
+		}
+		self.state.finish_verify(tag)
+	}
+	
+	/// Resets the state to its initial state (i.e. with the same parameters and key) so that it can
+	/// be reused after `finish`
+	///
+	/// _Note: The last node flag is reset to its initial value, too; the first reset compresses the
+	/// key block of the initial state ahead, so that later resets do not compress it again and the
+	/// initial state keeps the chain value after the key block instead of the key_
+	pub fn reset(&mut self) -> &mut Self {
+		self.initial.precompress();
+		self.state = self.initial.clone();
+		self
+	}
+	
+	/// The length of an exported state
+	pub const EXPORT_LEN: usize = b2b_impl::EXPORT_LEN;
+	/// Exports the state into a versioned, self-describing and corruption-checked byte format that can
+	/// be imported using `import` to resume the computation (e.g. after a process restart)
+	///
+	/// _Note: The exported state of a keyed state contains secret data (e.g. the key block if no data
+	/// has been absorbed yet or the final chain values of a reset state) and must be kept as secret as
+	/// the key_
+	pub fn export(&self) -> Result<[u8; Self::EXPORT_LEN], Blake2Error> {
+		if self.state.is_finished() {
+			Err(Blake2Error::ApiMisuse("The state has already been finalized"))?
+		}
+		Ok(self.state.export())
+	}
+	/// Validates and imports a state that has been exported using `export`
+	///
+	/// _Note: The imported state is also the initial state for `reset`_
+	pub fn import(bytes: &[u8]) -> Result<Self, Blake2Error> {
+		Ok(Self::new(B2Impl::import(bytes)?))
+	}
+	
+	/// The digest length of the state
+	pub fn hash_len(&self) -> usize {
+		self.state.hash_len()
+	}
+}
\ No newline at end of file
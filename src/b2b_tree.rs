@@ -0,0 +1,176 @@
+use crate::{ Blake2Error, b2b_impl::B2Impl, wipe::{ wipe, wipe_slice } };
+use std::mem;
+
+
+/// An inner tree node that absorbs the digests of its children
+#[derive(Clone)]
+struct Node {
+	state: B2Impl,
+	offset: u64,
+	children: u64
+}
+impl Node {
+	/// Creates a new inner node at `offset` and `node_depth`
+	fn new(params: &[u8; 64], offset: u64, node_depth: u8) -> Self {
+		Self { state: B2Impl::init_node(params, offset, node_depth, 64, &[]), offset, children: 0 }
+	}
+}
+
+
+/// An incremental [Blake2b](https://blake2.net/blake2.pdf) tree hash builder created by
+/// `Blake2bParams::to_tree`
+///
+/// Leaves are added in order and hashed (with the key) at node depth `0`; each inner node absorbs
+/// the digests of up to `fanout` children (or all children if the fan-out is unlimited) and the root
+/// is the first level that consists of a single node. Like Blake2bp, all nodes share the parameter
+/// block (including the root's digest length) except for their node offset and depth, and the last
+/// node of each level has the last node flag set. The key is wiped on drop.
+#[derive(Clone)]
+pub struct Blake2bTree {
+	params: [u8; 64],
+	key: Vec<u8>,
+	hash_len: usize,
+	inner_len: usize,
+	fanout: u64,
+	leaf_len: usize,
+	capacity: Option<u64>,
+	
+	leaf: Option<B2Impl>,
+	leaves: u64,
+	leaf_is_short: bool,
+	levels: Vec<Node>, // `levels[i]` contains the current node with node depth `i + 1`
+	finished: bool
+}
+impl Blake2bTree {
+	/// Creates a new tree hash builder with the validated parameter block `params` and `key`
+	pub(crate) fn new(params: [u8; 64], key: &[u8]) -> Self {
+		// Read the tree parameters
+		let (hash_len, inner_len) = (params[0] as usize, params[17] as usize);
+		let (fanout, depth) = (params[2], params[3]);
+		let mut leaf_len = [0; 4];
+		leaf_len.copy_from_slice(&params[4..8]);
+		
+		// Compute the maximum amount of leaves (`None` if unlimited)
+		let capacity = match fanout {
+			0 => None,
+			fanout => (fanout as u64).checked_pow(depth as u32 - 1)
+		};
+		
+		Self {
+			params, key: key.to_vec(), hash_len, inner_len, fanout: fanout as u64,
+			leaf_len: u32::from_le_bytes(leaf_len) as usize, capacity,
+			leaf: None, leaves: 0, leaf_is_short: false, levels: Vec::new(), finished: false
+		}
+	}
+	
+	/// Finalizes `state` with the `last_node` flag and returns the full digest
+	fn digest(state: &mut B2Impl, last_node: bool) -> [u8; 64] {
+		let mut digest = [0; 64];
+		state.set_last_node(last_node).finish(&mut digest);
+		digest
+	}
+	/// Absorbs a child digest into the current node with `node_depth`
+	fn absorb(&mut self, node_depth: u8, digest: &[u8; 64]) {
+		// Create the level if necessary
+		let i = node_depth as usize - 1;
+		if i == self.levels.len() { self.levels.push(Node::new(&self.params, 0, node_depth)) }
+		
+		// Finalize the current node if it is full and absorb it into its parent
+		if self.fanout != 0 && self.levels[i].children == self.fanout {
+			let next = Node::new(&self.params, self.levels[i].offset + 1, node_depth);
+			let mut node = mem::replace(&mut self.levels[i], next);
+			
+			let mut node_digest = Self::digest(&mut node.state, false);
+			self.absorb(node_depth + 1, &node_digest);
+			wipe(&mut node_digest);
+		}
+		
+		// Absorb the digest
+		let node = &mut self.levels[i];
+		node.state.update(&digest[..self.inner_len]);
+		node.children += 1;
+	}
+	
+	/// Adds the next `leaf` to the tree
+	///
+	/// _Note: If a leaf length is set, all leaves except for the last one must have exactly this
+	/// length_
+	pub fn add_leaf(&mut self, leaf: &[u8]) -> Result<&mut Self, Blake2Error> {
+		// Validate the state and the leaf
+		if self.finished {
+			Err(Blake2Error::ApiMisuse("The tree has already been finalized"))?
+		}
+		if self.leaf_len != 0 {
+			check_in!(leaf.len(), 0..=self.leaf_len);
+		}
+		if self.leaf_is_short {
+			Err(Blake2Error::ApiMisuse("Only the last leaf may be shorter than the leaf length"))?
+		}
+		if Some(self.leaves) == self.capacity {
+			Err(Blake2Error::ApiMisuse("The tree exceeds the maximal depth"))?
+		}
+		
+		// Finalize the previous leaf now that we know that it is not the last one
+		if let Some(mut previous) = self.leaf.take() {
+			let mut digest = Self::digest(&mut previous, false);
+			self.absorb(1, &digest);
+			wipe(&mut digest);
+		}
+		
+		// Hash the leaf but defer its finalization until we know whether it is the last leaf or not
+		let mut state = B2Impl::init_node(&self.params, self.leaves, 0, 64, &self.key);
+		state.update(leaf);
+		
+		self.leaf = Some(state);
+		self.leaves += 1;
+		self.leaf_is_short = self.leaf_len != 0 && leaf.len() < self.leaf_len;
+		Ok(self)
+	}
+	
+	/// Finalizes the tree and computes the root digest into `buf`
+	///
+	/// _Note: A tree without leaves is hashed as a tree with a single empty leaf_
+	pub fn finish(&mut self, buf: &mut[u8]) -> Result<usize, Blake2Error> {
+		// Validate the state and `buf`
+		if self.finished {
+			Err(Blake2Error::ApiMisuse("The tree has already been finalized"))?
+		}
+		check_eq!(buf.len(), self.hash_len);
+		if self.leaf.is_none() { self.add_leaf(&[])?; }
+		
+		// Finalize the last leaf
+		let mut leaf = self.leaf.take().expect("There is no leaf to finalize");
+		let mut digest = Self::digest(&mut leaf, true);
+		self.absorb(1, &digest);
+		wipe(&mut digest);
+		
+		// Finalize the last node of each level and absorb it into its parent until we reach the root
+		let mut i = 0;
+		loop {
+			let is_root = i + 1 == self.levels.len() && self.levels[i].offset == 0;
+			let mut digest = Self::digest(&mut self.levels[i].state, true);
+			if is_root {
+				buf.copy_from_slice(&digest[..self.hash_len]);
+				wipe(&mut digest);
+				break;
+			}
+			
+			self.absorb(i as u8 + 2, &digest);
+			wipe(&mut digest);
+			i += 1;
+		}
+		
+		self.finished = true;
+		Ok(buf.len())
+	}
+	
+	/// The digest length of the root
+	pub fn hash_len(&self) -> usize {
+		self.hash_len
+	}
+}
+impl Drop for Blake2bTree {
+	fn drop(&mut self) {
+		wipe_slice(&mut self.key);
+	}
+}
\ No newline at end of file
@@ -0,0 +1,377 @@
+use crate::{ b2b_impl::{ IV, SIGMA }, wipe::wipe };
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+
+/// Combines the message words `$x` and `$y` from the message word pairs `$m` into one 128 bit lane
+///
+/// _Note: The rounds are unrolled so that the indices are constant and only one branch remains_
+macro_rules! pair {
+	($m:expr, $x:expr, $y:expr, $unpacklo:ident, $unpackhi:ident, $alignr:ident, $blend:ident, $mask:expr) => ({
+		let (x, y) = ($x as usize, $y as usize);
+		match (x % 2, y % 2) {
+			(0, 0) => $unpacklo($m[x / 2], $m[y / 2]),
+			(1, 1) => $unpackhi($m[x / 2], $m[y / 2]),
+			(1, _) => $alignr($m[y / 2], $m[x / 2], 8),
+			_ => $blend($m[x / 2], $m[y / 2], $mask)
+		}
+	});
+}
+/// Loads the message words for the given `SIGMA`-indices (first index = lowest lane)
+macro_rules! load_m128 {
+	($m:expr, $s:expr, $i0:expr, $i1:expr) => ({
+		pair!($m, $s[$i0], $s[$i1], _mm_unpacklo_epi64, _mm_unpackhi_epi64, _mm_alignr_epi8, _mm_blend_epi16, 0b1111_0000)
+	});
+}
+/// Loads the message words for the given `SIGMA`-indices (first index = lowest lane)
+macro_rules! load_m256 {
+	($m:expr, $s:expr, $i0:expr, $i1:expr, $i2:expr, $i3:expr) => ({
+		macro_rules! pair256 {
+			($x:expr, $y:expr) => ({
+				pair!($m, $x, $y, _mm256_unpacklo_epi64, _mm256_unpackhi_epi64, _mm256_alignr_epi8, _mm256_blend_epi32, 0b1100_1100)
+			});
+		}
+		let (lo, hi) = (pair256!($s[$i0], $s[$i1]), pair256!($s[$i2], $s[$i3]));
+		_mm256_blend_epi32(lo, hi, 0b1111_0000)
+	});
+}
+
+
+/// The SSE4.1 compression function
+///
+/// _Safety: The CPU must support SSE4.1_
+#[target_feature(enable = "sse4.1")]
+pub unsafe fn compress_sse41(h: &mut[u64; 8], m: &[u64; 16], t: &[u64; 2], f: &[u64; 2]) {
+	// Byte shuffles for the rotations by 24 and 16 bits
+	let rot24 = _mm_setr_epi8(3, 4, 5, 6, 7, 0, 1, 2, 11, 12, 13, 14, 15, 8, 9, 10);
+	let rot16 = _mm_setr_epi8(2, 3, 4, 5, 6, 7, 0, 1, 10, 11, 12, 13, 14, 15, 8, 9);
+	
+	// Load the state as two half rows per row
+	macro_rules! load {
+		($p:expr) => ({ _mm_loadu_si128($p.as_ptr() as *const __m128i) });
+	}
+	let (h0, h1, h2, h3) = (load!(h[0..2]), load!(h[2..4]), load!(h[4..6]), load!(h[6..8]));
+	let (mut a0, mut a1, mut b0, mut b1) = (h0, h1, h2, h3);
+	let (mut c0, mut c1) = (load!(IV[0..2]), load!(IV[2..4]));
+	let mut d0 = _mm_xor_si128(load!(IV[4..6]), _mm_set_epi64x(t[1] as i64, t[0] as i64));
+	let mut d1 = _mm_xor_si128(load!(IV[6..8]), _mm_set_epi64x(f[1] as i64, f[0] as i64));
+	
+	// Load the message word pairs
+	let mut m = [
+		load!(m[0..2]), load!(m[2..4]), load!(m[4..6]), load!(m[6..8]),
+		load!(m[8..10]), load!(m[10..12]), load!(m[12..14]), load!(m[14..16])
+	];
+	
+	// Half G functions on all four columns (or diagonals)
+	macro_rules! g1 {
+		($m0:expr, $m1:expr) => ({
+			a0 = _mm_add_epi64(_mm_add_epi64(a0, b0), $m0);
+			a1 = _mm_add_epi64(_mm_add_epi64(a1, b1), $m1);
+			d0 = _mm_shuffle_epi32(_mm_xor_si128(d0, a0), 0b10_11_00_01);
+			d1 = _mm_shuffle_epi32(_mm_xor_si128(d1, a1), 0b10_11_00_01);
+			c0 = _mm_add_epi64(c0, d0);
+			c1 = _mm_add_epi64(c1, d1);
+			b0 = _mm_shuffle_epi8(_mm_xor_si128(b0, c0), rot24);
+			b1 = _mm_shuffle_epi8(_mm_xor_si128(b1, c1), rot24);
+		});
+	}
+	macro_rules! g2 {
+		($m0:expr, $m1:expr) => ({
+			a0 = _mm_add_epi64(_mm_add_epi64(a0, b0), $m0);
+			a1 = _mm_add_epi64(_mm_add_epi64(a1, b1), $m1);
+			d0 = _mm_shuffle_epi8(_mm_xor_si128(d0, a0), rot16);
+			d1 = _mm_shuffle_epi8(_mm_xor_si128(d1, a1), rot16);
+			c0 = _mm_add_epi64(c0, d0);
+			c1 = _mm_add_epi64(c1, d1);
+			b0 = _mm_xor_si128(b0, c0);
+			b1 = _mm_xor_si128(b1, c1);
+			b0 = _mm_xor_si128(_mm_srli_epi64(b0, 63), _mm_add_epi64(b0, b0));
+			b1 = _mm_xor_si128(_mm_srli_epi64(b1, 63), _mm_add_epi64(b1, b1));
+		});
+	}
+	
+	// Do rounds (unrolled so that the message indices are constant)
+	macro_rules! round {
+		($r:expr) => ({
+			let s = &SIGMA[$r];
+			// Columns
+			g1!(load_m128!(m, s, 0, 2), load_m128!(m, s, 4, 6));
+			g2!(load_m128!(m, s, 1, 3), load_m128!(m, s, 5, 7));
+			
+			// Diagonalize
+			let (t0, t1) = (_mm_alignr_epi8(b1, b0, 8), _mm_alignr_epi8(b0, b1, 8));
+			b0 = t0;
+			b1 = t1;
+			core::mem::swap(&mut c0, &mut c1);
+			let (t0, t1) = (_mm_alignr_epi8(d1, d0, 8), _mm_alignr_epi8(d0, d1, 8));
+			d0 = t1;
+			d1 = t0;
+			
+			// Diagonals
+			g1!(load_m128!(m, s, 8, 10), load_m128!(m, s, 12, 14));
+			g2!(load_m128!(m, s, 9, 11), load_m128!(m, s, 13, 15));
+			
+			// Undiagonalize
+			let (t0, t1) = (_mm_alignr_epi8(b0, b1, 8), _mm_alignr_epi8(b1, b0, 8));
+			b0 = t0;
+			b1 = t1;
+			core::mem::swap(&mut c0, &mut c1);
+			let (t0, t1) = (_mm_alignr_epi8(d0, d1, 8), _mm_alignr_epi8(d1, d0, 8));
+			d0 = t1;
+			d1 = t0;
+		});
+	}
+	round!(0); round!(1); round!(2); round!(3); round!(4); round!(5);
+	round!(6); round!(7); round!(8); round!(9); round!(10); round!(11);
+	
+	// Update the state
+	macro_rules! store {
+		($p:expr, $x:expr) => ({ _mm_storeu_si128($p.as_mut_ptr() as *mut __m128i, $x) });
+	}
+	store!(h[0..2], _mm_xor_si128(h0, _mm_xor_si128(a0, c0)));
+	store!(h[2..4], _mm_xor_si128(h1, _mm_xor_si128(a1, c1)));
+	store!(h[4..6], _mm_xor_si128(h2, _mm_xor_si128(b0, d0)));
+	store!(h[6..8], _mm_xor_si128(h3, _mm_xor_si128(b1, d1)));
+	wipe(&mut m);
+}
+
+
+/// Rotates the 64 bit lanes of `$x` right by `$n` (`32`, `24`, `16` or `63`) using AVX2
+macro_rules! ror_avx2 {
+	($x:expr, 32) => ({ _mm256_shuffle_epi32($x, 0b10_11_00_01) });
+	($x:expr, 24) => ({
+		let rot24 = _mm256_setr_epi8(
+			3, 4, 5, 6, 7, 0, 1, 2, 11, 12, 13, 14, 15, 8, 9, 10,
+			3, 4, 5, 6, 7, 0, 1, 2, 11, 12, 13, 14, 15, 8, 9, 10
+		);
+		_mm256_shuffle_epi8($x, rot24)
+	});
+	($x:expr, 16) => ({
+		let rot16 = _mm256_setr_epi8(
+			2, 3, 4, 5, 6, 7, 0, 1, 10, 11, 12, 13, 14, 15, 8, 9,
+			2, 3, 4, 5, 6, 7, 0, 1, 10, 11, 12, 13, 14, 15, 8, 9
+		);
+		_mm256_shuffle_epi8($x, rot16)
+	});
+	($x:expr, 63) => ({
+		let x = $x;
+		_mm256_xor_si256(_mm256_srli_epi64(x, 63), _mm256_add_epi64(x, x))
+	});
+}
+/// Rotates the 64 bit lanes of `$x` right by `$n` using the native AVX-512VL rotation
+macro_rules! ror_avx512 {
+	($x:expr, $n:literal) => ({ _mm256_ror_epi64($x, $n) });
+}
+/// The compression function body for one row per 256 bit register with the rotation macro `$ror`
+macro_rules! compress256 {
+	($h:expr, $m:expr, $t:expr, $f:expr, $ror:ident) => ({
+		let (h, m, t, f) = ($h, $m, $t, $f);
+		
+		// Load the state as one row per register
+		macro_rules! load {
+			($p:expr) => ({ _mm256_loadu_si256($p.as_ptr() as *const __m256i) });
+		}
+		let (h0, h1) = (load!(h[0..4]), load!(h[4..8]));
+		let (mut a, mut b, mut c) = (h0, h1, load!(IV[0..4]));
+		let tf = _mm256_set_epi64x(f[1] as i64, f[0] as i64, t[1] as i64, t[0] as i64);
+		let mut d = _mm256_xor_si256(load!(IV[4..8]), tf);
+		
+		// Load the message word pairs into both 128 bit lanes
+		macro_rules! load2 {
+			($p:expr) => ({ _mm256_broadcastsi128_si256(_mm_loadu_si128($p.as_ptr() as *const __m128i)) });
+		}
+		let mut m = [
+			load2!(m[0..2]), load2!(m[2..4]), load2!(m[4..6]), load2!(m[6..8]),
+			load2!(m[8..10]), load2!(m[10..12]), load2!(m[12..14]), load2!(m[14..16])
+		];
+		
+		// Half G functions on all four columns (or diagonals)
+		macro_rules! g1 {
+			($mi:expr) => ({
+				a = _mm256_add_epi64(_mm256_add_epi64(a, b), $mi);
+				d = $ror!(_mm256_xor_si256(d, a), 32);
+				c = _mm256_add_epi64(c, d);
+				b = $ror!(_mm256_xor_si256(b, c), 24);
+			});
+		}
+		macro_rules! g2 {
+			($mi:expr) => ({
+				a = _mm256_add_epi64(_mm256_add_epi64(a, b), $mi);
+				d = $ror!(_mm256_xor_si256(d, a), 16);
+				c = _mm256_add_epi64(c, d);
+				b = $ror!(_mm256_xor_si256(b, c), 63);
+			});
+		}
+		
+		// Do rounds (unrolled so that the message indices are constant)
+		macro_rules! round {
+			($r:expr) => ({
+				let s = &SIGMA[$r];
+				// Columns
+				g1!(load_m256!(m, s, 0, 2, 4, 6));
+				g2!(load_m256!(m, s, 1, 3, 5, 7));
+				
+				// Diagonalize
+				b = _mm256_permute4x64_epi64(b, 0b00_11_10_01);
+				c = _mm256_permute4x64_epi64(c, 0b01_00_11_10);
+				d = _mm256_permute4x64_epi64(d, 0b10_01_00_11);
+				
+				// Diagonals
+				g1!(load_m256!(m, s, 8, 10, 12, 14));
+				g2!(load_m256!(m, s, 9, 11, 13, 15));
+				
+				// Undiagonalize
+				b = _mm256_permute4x64_epi64(b, 0b10_01_00_11);
+				c = _mm256_permute4x64_epi64(c, 0b01_00_11_10);
+				d = _mm256_permute4x64_epi64(d, 0b00_11_10_01);
+			});
+		}
+		round!(0); round!(1); round!(2); round!(3); round!(4); round!(5);
+		round!(6); round!(7); round!(8); round!(9); round!(10); round!(11);
+		
+		// Update the state
+		macro_rules! store {
+			($p:expr, $x:expr) => ({ _mm256_storeu_si256($p.as_mut_ptr() as *mut __m256i, $x) });
+		}
+		store!(h[0..4], _mm256_xor_si256(h0, _mm256_xor_si256(a, c)));
+		store!(h[4..8], _mm256_xor_si256(h1, _mm256_xor_si256(b, d)));
+		wipe(&mut m);
+	});
+}
+
+
+/// The AVX2 compression function
+///
+/// _Safety: The CPU must support AVX2_
+#[target_feature(enable = "avx2")]
+pub unsafe fn compress_avx2(h: &mut[u64; 8], m: &[u64; 16], t: &[u64; 2], f: &[u64; 2]) {
+	compress256!(h, m, t, f, ror_avx2)
+}
+
+
+/// The AVX-512VL compression function
+///
+/// _Safety: The CPU must support AVX-512VL (which implies AVX-512F and AVX2)_
+#[target_feature(enable = "avx512vl")]
+pub unsafe fn compress_avx512(h: &mut[u64; 8], m: &[u64; 16], t: &[u64; 2], f: &[u64; 2]) {
+	compress256!(h, m, t, f, ror_avx512)
+}
+
+/// Rotates the 64 bit lanes of the 512 bit vector `$x` right by `$n` using AVX-512F
+macro_rules! ror_avx512_512 {
+	($x:expr, $n:literal) => ({ _mm512_ror_epi64($x, $n) });
+}
+/// The compression function body for multiple states that are interleaved word-wise (i.e. each
+/// vector contains the same word of all states) with the vector operation macros `$ops`
+macro_rules! compress_lanes {
+	($h:expr, $m:expr, $t:expr, $f:expr, $ops:ident, $ror:ident) => ({
+		let (h, m, t, f) = ($h, $m, $t, $f);
+		
+		// Load the states and the messages word-wise
+		let mut v = [
+			$ops!(gather, h, 0), $ops!(gather, h, 1), $ops!(gather, h, 2), $ops!(gather, h, 3),
+			$ops!(gather, h, 4), $ops!(gather, h, 5), $ops!(gather, h, 6), $ops!(gather, h, 7),
+			$ops!(splat, IV[0]), $ops!(splat, IV[1]), $ops!(splat, IV[2]), $ops!(splat, IV[3]),
+			$ops!(xor, $ops!(splat, IV[4]), $ops!(gather, t, 0)), $ops!(xor, $ops!(splat, IV[5]), $ops!(gather, t, 1)),
+			$ops!(xor, $ops!(splat, IV[6]), $ops!(gather, f, 0)), $ops!(xor, $ops!(splat, IV[7]), $ops!(gather, f, 1))
+		];
+		let mut h0 = [v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7]];
+		let mut m = [
+			$ops!(gather, m, 0), $ops!(gather, m, 1), $ops!(gather, m, 2), $ops!(gather, m, 3),
+			$ops!(gather, m, 4), $ops!(gather, m, 5), $ops!(gather, m, 6), $ops!(gather, m, 7),
+			$ops!(gather, m, 8), $ops!(gather, m, 9), $ops!(gather, m, 10), $ops!(gather, m, 11),
+			$ops!(gather, m, 12), $ops!(gather, m, 13), $ops!(gather, m, 14), $ops!(gather, m, 15)
+		];
+		
+		// G function of compression
+		macro_rules! g {
+			($s:expr, $i:expr, $a:expr, $b:expr, $c:expr, $d:expr) => ({
+				v[$a] = $ops!(add, $ops!(add, v[$a], v[$b]), m[$s[2 * $i] as usize]);
+				v[$d] = $ror!($ops!(xor, v[$d], v[$a]), 32);
+				v[$c] = $ops!(add, v[$c], v[$d]);
+				v[$b] = $ror!($ops!(xor, v[$b], v[$c]), 24);
+				v[$a] = $ops!(add, $ops!(add, v[$a], v[$b]), m[$s[2 * $i + 1] as usize]);
+				v[$d] = $ror!($ops!(xor, v[$d], v[$a]), 16);
+				v[$c] = $ops!(add, v[$c], v[$d]);
+				v[$b] = $ror!($ops!(xor, v[$b], v[$c]), 63);
+			});
+		}
+		// Do rounds (unrolled so that the message indices are constant)
+		macro_rules! round {
+			($r:expr) => ({
+				let s = &SIGMA[$r];
+				g!(s, 0, 0, 4,  8, 12);
+				g!(s, 1, 1, 5,  9, 13);
+				g!(s, 2, 2, 6, 10, 14);
+				g!(s, 3, 3, 7, 11, 15);
+				g!(s, 4, 0, 5, 10, 15);
+				g!(s, 5, 1, 6, 11, 12);
+				g!(s, 6, 2, 7,  8, 13);
+				g!(s, 7, 3, 4,  9, 14);
+			});
+		}
+		round!(0); round!(1); round!(2); round!(3); round!(4); round!(5);
+		round!(6); round!(7); round!(8); round!(9); round!(10); round!(11);
+		
+		// Update the states
+		for i in 0..8 {
+			$ops!(scatter, h, i, $ops!(xor, h0[i], $ops!(xor, v[i], v[i + 8])));
+		}
+		wipe(&mut v);
+		wipe(&mut h0);
+		wipe(&mut m);
+	});
+}
+
+
+/// The AVX2 operations on 4 interleaved 64 bit words
+macro_rules! ops4 {
+	(gather, $a:expr, $i:expr) => ({
+		_mm256_setr_epi64x($a[0][$i] as i64, $a[1][$i] as i64, $a[2][$i] as i64, $a[3][$i] as i64)
+	});
+	(scatter, $a:expr, $i:expr, $x:expr) => ({
+		let mut words = [0u64; 4];
+		_mm256_storeu_si256(words.as_mut_ptr() as *mut __m256i, $x);
+		for (lane, word) in words.iter().enumerate() { $a[lane][$i] = *word }
+		wipe(&mut words);
+	});
+	(splat, $x:expr) => ({ _mm256_set1_epi64x($x as i64) });
+	(add, $a:expr, $b:expr) => ({ _mm256_add_epi64($a, $b) });
+	(xor, $a:expr, $b:expr) => ({ _mm256_xor_si256($a, $b) });
+}
+/// The 4-way AVX2 compression function for 4 independent states
+///
+/// _Safety: The CPU must support AVX2_
+#[target_feature(enable = "avx2")]
+pub unsafe fn compress4_avx2(h: &mut[[u64; 8]; 4], m: &[[u64; 16]; 4], t: &[[u64; 2]; 4], f: &[[u64; 2]; 4]) {
+	compress_lanes!(h, m, t, f, ops4, ror_avx2)
+}
+
+
+/// The AVX-512F operations on 8 interleaved 64 bit words
+macro_rules! ops8 {
+	(gather, $a:expr, $i:expr) => ({
+		_mm512_setr_epi64(
+			$a[0][$i] as i64, $a[1][$i] as i64, $a[2][$i] as i64, $a[3][$i] as i64,
+			$a[4][$i] as i64, $a[5][$i] as i64, $a[6][$i] as i64, $a[7][$i] as i64
+		)
+	});
+	(scatter, $a:expr, $i:expr, $x:expr) => ({
+		let mut words = [0u64; 8];
+		_mm512_storeu_si512(words.as_mut_ptr() as *mut __m512i, $x);
+		for (lane, word) in words.iter().enumerate() { $a[lane][$i] = *word }
+		wipe(&mut words);
+	});
+	(splat, $x:expr) => ({ _mm512_set1_epi64($x as i64) });
+	(add, $a:expr, $b:expr) => ({ _mm512_add_epi64($a, $b) });
+	(xor, $a:expr, $b:expr) => ({ _mm512_xor_si512($a, $b) });
+}
+/// The 8-way AVX-512 compression function for 8 independent states
+///
+/// _Safety: The CPU must support AVX-512VL (which implies AVX-512F and AVX2)_
+#[target_feature(enable = "avx512vl")]
+pub unsafe fn compress8_avx512(h: &mut[[u64; 8]; 8], m: &[[u64; 16]; 8], t: &[[u64; 2]; 8], f: &[[u64; 2]; 8]) {
+	compress_lanes!(h, m, t, f, ops8, ror_avx512_512)
+}
\ No newline at end of file
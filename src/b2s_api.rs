@@ -0,0 +1,234 @@
+use crate::{ Blake2Error, b2s_impl::B2sImpl };
+use crypto_api::{
+	rng::{ SecureRng, SecKeyGen }, kdf::{ KdfInfo, Kdf },
+	mac::{ MacInfo, Mac, VarlenMac, StreamingMac, StreamingVarlenMac },
+	hash::{ HashInfo, Hash, VarlenHash, StreamingHash, StreamingVarlenHash }
+};
+use std::error::Error;
+
+
+/// An implementation of [Blake2s](https://blake2.net/blake2.pdf)
+#[derive(Default, Clone)]
+pub struct Blake2s {
+	state: Option<B2sImpl>,
+	initial: Option<B2sImpl>,
+	personal: Vec<u8>
+}
+impl Blake2s {
+	/// Creates a new `Blake2s` instance that uses `personal` as personalization string for all hash
+	/// and MAC operations
+	///
+	/// _Note: The personalization string must not be longer than 8 bytes; the KDF always uses its
+	/// `info` argument as personalization string instead_
+	pub fn with_personal(personal: &[u8]) -> Result<Self, Blake2Error> {
+		check_in!(personal.len(), 0..=8);
+		Ok(Self { state: None, initial: None, personal: personal.to_vec() })
+	}
+	
+	/// Creates a `Hash` instance with `Blake2s` as underlying hash
+	pub fn hash() -> Box<dyn Hash> {
+		Box::new(Self::default())
+	}
+	/// Creates a `VarlenHash` instance with `Blake2s` as underlying hash
+	pub fn varlen_hash() -> Box<dyn VarlenHash> {
+		Box::new(Self::default())
+	}
+	/// Creates a `Mac` instance with `Blake2s` as underlying MAC
+	pub fn mac() -> Box<dyn Mac> {
+		Box::new(Self::default())
+	}
+	/// Creates a `VarlenMac` instance with `Blake2s` as underlying MAC
+	pub fn varlen_mac() -> Box<dyn VarlenMac> {
+		Box::new(Self::default())
+	}
+	/// Creates a `Kdf` instance with `Blake2s` as underlying KDF
+	pub fn kdf() -> Box<dyn Kdf> {
+		Box::new(Self::default())
+	}
+	
+	/// Creates a `StreamingHash` instance with `Blake2s` as underlying hash
+	pub fn streaming_hash() -> Box<dyn StreamingHash> {
+		Box::new(Self::default())
+	}
+	/// Creates a `StreamingVarlenHash` instance with `Blake2s` as underlying hash
+	pub fn streaming_varlen_hash() -> Box<dyn StreamingVarlenHash> {
+		Box::new(Self::default())
+	}
+	/// Creates a `StreamingMac` instance with `Blake2s` as underlying MAC
+	pub fn streaming_mac() -> Box<dyn StreamingMac> {
+		Box::new(Self::default())
+	}
+	/// Creates a `StreamingVarlenMac` instance with `Blake2s` as underlying MAC
+	pub fn streaming_varlen_mac() -> Box<dyn StreamingVarlenMac> {
+		Box::new(Self::default())
+	}
+	
+	/// Resets the streaming hash or MAC to its initial state after the last `init` (i.e. with the same
+	/// digest length and key) so that it can be reused after `finish`
+	///
+	/// _Note: The key block is only compressed once for all resets (see `Blake2bState::reset`)_
+	pub fn reset(&mut self) -> Result<(), Blake2Error> {
+		let initial = self.initial.as_mut()
+			.ok_or(Blake2Error::ApiMisuse("The state is not initialized"))?;
+		initial.precompress();
+		self.state = Some(initial.clone());
+		Ok(())
+	}
+	/// Sets `state` as the current and the initial streaming state
+	fn set_state(&mut self, state: B2sImpl) {
+		self.initial = Some(state.clone());
+		self.state = Some(state);
+	}
+	
+	/// Returns info about the hash
+	fn hash_info() -> HashInfo {
+		HashInfo { name: "Blake2s", hash_len: 32, hash_len_r: 1..(32 + 1) }
+	}
+	/// Returns info about the MAC
+	fn mac_info() -> MacInfo {
+		MacInfo { name: "Blake2s", is_otm: false, mac_len: 32, mac_len_r: 1..(32 + 1), key_len_r: 1..(32 + 1) }
+	}
+	/// Returns info about the KDF
+	fn kdf_info() -> KdfInfo {
+		KdfInfo {
+			name: "Blake2s", output_len_r: 1..(32 + 1), key_len_r: 1..(32 + 1),
+			salt_len_r: 0..(8 + 1), info_len_r: 0..(8 + 1)
+		}
+	}
+}
+impl SecKeyGen for Blake2s {
+	fn new_sec_key(&self, buf: &mut[u8], rng: &mut dyn SecureRng) -> Result<usize, Box<dyn Error + 'static>> {
+		check_in!(buf.len(), 1..=32);
+		
+		rng.random(buf)?;
+		Ok(buf.len())
+	}
+}
+
+impl Hash for Blake2s {
+	fn info(&self) -> HashInfo {
+		Self::hash_info()
+	}
+	
+	fn hash(&self, buf: &mut[u8], data: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+		check_eq!(buf.len(), 32);
+		self.varlen_hash(buf, data)
+	}
+}
+impl VarlenHash for Blake2s {
+	fn varlen_hash(&self, buf: &mut[u8], data: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+		check_in!(buf.len(), 1..=32);
+		
+		B2sImpl::init(buf.len(), &self.personal).update(data).finish(buf);
+		Ok(buf.len())
+	}
+}
+
+impl Mac for Blake2s {
+	fn info(&self) -> MacInfo {
+		Self::mac_info()
+	}
+	
+	fn auth(&self, buf: &mut[u8], data: &[u8], key: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+		check_eq!(buf.len(), 32);
+		self.varlen_auth(buf, data, key)
+	}
+}
+impl VarlenMac for Blake2s {
+	fn varlen_auth(&self, buf: &mut[u8], data: &[u8], key: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+		check_in!(buf.len(), 1..=32);
+		check_in!(key.len(), 1..=32);
+		
+		B2sImpl::init_mac(buf.len(), key, &self.personal).update(data).finish(buf);
+		Ok(buf.len())
+	}
+}
+
+impl Kdf for Blake2s {
+	fn info(&self) -> KdfInfo {
+		Self::kdf_info()
+	}
+	
+	fn derive(&self, buf: &mut[u8], base_key: &[u8], salt: &[u8], info: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
+		check_in!(buf.len(), 1..=32);
+		check_in!(base_key.len(), 1..=32);
+		check_in!(salt.len(), 0..=8);
+		check_in!(info.len(), 0..=8);
+		
+		B2sImpl::init_kdf(buf.len(), base_key, salt, info).finish(buf);
+		Ok(())
+	}
+}
+
+impl StreamingHash for Blake2s {
+	fn info(&self) -> HashInfo {
+		let mut info = Self::hash_info();
+		if let Some(s) = self.state.as_ref() { info.hash_len = s.hash_len() }
+		info
+	}
+	
+	fn init(&mut self) -> Result<(), Box<dyn Error + 'static>> {
+		self.set_state(B2sImpl::init(32, &self.personal));
+		Ok(())
+	}
+	fn update(&mut self, input: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
+		self.state.as_mut()
+			.ok_or(Blake2Error::ApiMisuse("The hash is not initialized"))?
+			.update(input);
+		Ok(())
+	}
+	fn finish(&mut self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+		let mut state = self.state.take()
+			.ok_or(Blake2Error::ApiMisuse("The hash is not initialized"))?;
+		check_eq!(buf.len(), state.hash_len());
+		
+		state.finish(buf);
+		Ok(buf.len())
+	}
+}
+impl StreamingVarlenHash for Blake2s {
+	fn varlen_init(&mut self, hash_len: usize) -> Result<(), Box<dyn Error + 'static>> {
+		check_in!(hash_len, 1..=32);
+		
+		self.set_state(B2sImpl::init(hash_len, &self.personal));
+		Ok(())
+	}
+}
+
+impl StreamingMac for Blake2s {
+	fn info(&self) -> MacInfo {
+		let mut info = Self::mac_info();
+		if let Some(s) = self.state.as_ref() { info.mac_len = s.hash_len() }
+		info
+	}
+	
+	fn init(&mut self, key: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
+		check_in!(key.len(), 1..=32);
+		
+		self.set_state(B2sImpl::init_mac(32, key, &self.personal));
+		Ok(())
+	}
+	fn update(&mut self, data: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
+		self.state.as_mut()
+			.ok_or(Blake2Error::ApiMisuse("The MAC is not initialized"))?
+			.update(data);
+		Ok(())
+	}
+	fn finish(&mut self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+		let mut state = self.state.take()
+			.ok_or(Blake2Error::ApiMisuse("The MAC is not initialized"))?;
+		check_eq!(buf.len(), state.hash_len());
+		
+		state.finish(buf);
+		Ok(buf.len())
+	}
+}
+impl StreamingVarlenMac for Blake2s {
+	fn varlen_init(&mut self, mac_len: usize, key: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
+		check_in!(mac_len, 1..=32);
+		check_in!(key.len(), 1..=32);
+		
+		self.set_state(B2sImpl::init_mac(mac_len, key, &self.personal));
+		Ok(())
+	}
+}
\ No newline at end of file
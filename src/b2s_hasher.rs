@@ -0,0 +1,46 @@
+use crate::{ Blake2Error, b2s_impl::B2sImpl };
+
+
+digest_type! {
+	/// A [Blake2s](https://blake2.net/blake2.pdf) digest with `1..=32` bytes
+	///
+	/// _Note: Digests compare in constant time (only the lengths are compared in variable time) because
+	/// they may be MAC tags; prefer `Blake2sHasher::verify` to check a MAC tag_
+	Blake2sDigest, 32
+}
+
+
+/// A statically dispatched streaming [Blake2s](https://blake2.net/blake2.pdf) hash or MAC with a
+/// digest length that is selected at runtime
+#[derive(Clone)]
+pub struct Blake2sHasher(B2sImpl);
+impl Blake2sHasher {
+	/// Creates a new hash state with the digest length `len` (`1..=32`)
+	pub fn new(len: usize) -> Result<Self, Blake2Error> {
+		check_in!(len, 1..=32);
+		Ok(Self(B2sImpl::init_mac(len, &[], &[])))
+	}
+	/// Creates a new MAC state with the digest length `len` (`1..=32`) and `key` (`1..=32` bytes)
+	pub fn new_keyed(len: usize, key: &[u8]) -> Result<Self, Blake2Error> {
+		check_in!(len, 1..=32);
+		check_in!(key.len(), 1..=32);
+		Ok(Self(B2sImpl::init_mac(len, key, &[])))
+	}
+	
+	/// Updates the state with `data`
+	pub fn update(&mut self, data: &[u8]) -> &mut Self {
+		self.0.update(data);
+		self
+	}
+	/// Finalizes the state and returns the digest
+	pub fn finalize(mut self) -> Blake2sDigest {
+		let mut digest = Blake2sDigest { bytes: [0; 32], len: self.0.hash_len() };
+		self.0.finish(&mut digest.bytes[..digest.len]);
+		digest
+	}
+	/// Finalizes the state and compares the digest with `tag` in constant time (returns
+	/// `Blake2Error::InvalidTag` if the tag does not match)
+	pub fn verify(mut self, tag: &[u8]) -> Result<(), Blake2Error> {
+		self.0.finish_verify(tag)
+	}
+}
@@ -0,0 +1,306 @@
+use crate::{ Blake2Error, ct, wipe::wipe };
+use core::cmp::min;
+
+
+/// Overflowing add
+macro_rules! add {
+	($a:expr, $b:expr) => ({ $a.wrapping_add($b) });
+	($a:expr, $b:expr, $c:expr) => ({ $a.wrapping_add($b).wrapping_add($c) });
+}
+
+
+const IV: [u32; 8] = [
+	0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+	0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19
+];
+const SIGMA: [[u8; 16]; 10] = [
+	[ 0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15],
+	[14, 10,  4,  8,  9, 15, 13,  6,  1, 12,  0,  2, 11,  7,  5,  3],
+	[11,  8, 12,  0,  5,  2, 15, 13, 10, 14,  3,  6,  7,  1,  9,  4],
+	[ 7,  9,  3,  1, 13, 12, 11, 14,  2,  6,  5, 10,  4,  0, 15,  8],
+	[ 9,  0,  5,  7,  2,  4, 10, 15, 14,  1, 11, 12,  6,  8,  3, 13],
+	[ 2, 12,  6, 10,  0, 11,  8,  3,  4, 13,  7,  5, 15, 14,  1,  9],
+	[12,  5,  1, 15, 14, 13,  4, 10,  0,  7,  6,  3,  9,  2,  8, 11],
+	[13, 11,  7, 14, 12,  1,  3,  9,  5,  0, 15,  4,  8,  6,  2, 10],
+	[ 6, 15, 14,  9, 11,  3,  0,  8, 12,  2, 13,  7,  1,  4, 10,  5],
+	[10,  2,  8,  4,  7,  6,  1,  5, 15, 11,  9, 14,  3, 12, 13,  0]
+];
+
+
+/// A Blake2s state with the real implementation
+///
+/// _Note: The state is wiped on drop; the chain value, counter and buffer are also wiped once the
+/// final hash has been computed_
+#[derive(Clone)]
+pub struct B2sImpl {
+	h: [u32; 8],
+	t: [u32; 2],
+	f: [u32; 2],
+	
+	buf: [u8; 64],
+	buf_len: usize,
+	hash_len: usize,
+	last_node: bool,
+	
+	/// The final chain values without and with the last node flag if the last block has been
+	/// compressed ahead (see `precompress`) and no data has been absorbed since then
+	empty: [[u32; 8]; 2],
+	precompressed: bool
+}
+impl B2sImpl {
+	/// Initializes the state as hash with `out_len` and the personalization string `personal`
+	#[cfg(feature = "std")]
+	pub fn init(out_len: usize, personal: &[u8]) -> Self {
+		Self::init_kdf(out_len, &[], &[], personal)
+	}
+	/// Initializes the state as MAC with `out_len`, `key` and the personalization string `personal`
+	pub fn init_mac(out_len: usize, key: &[u8], personal: &[u8]) -> Self {
+		Self::init_kdf(out_len, key, &[], personal)
+	}
+	/// Initializes the state as KDF with `out_len`, `base_key`, `salt` and `info`
+	///
+	/// _Note: `info` is stored in the personalization field of the parameter block_
+	pub fn init_kdf(out_len: usize, base_key: &[u8], salt: &[u8], info: &[u8]) -> Self {
+		// Validate the salt and info length
+		assert!(salt.len() <= 8, "Salt is too large");
+		assert!(info.len() <= 8, "Info is too large");
+		
+		// Create parameters
+		let mut p = [0; 32];
+		p[0] = out_len as u8;
+		p[1] = base_key.len() as u8;
+		p[2] = 1; // Fan-out
+		p[3] = 1; // Depth
+		
+		// Copy salt and info (= personalization)
+		p[16 .. 16 + salt.len()].copy_from_slice(salt);
+		p[24 .. 24 + info.len()].copy_from_slice(info);
+		
+		Self::init_params(&p, out_len, base_key)
+	}
+	/// Initializes the state with the raw parameter block `params`, `out_len` and `key`
+	///
+	/// _Note: `out_len` may differ from the digest length in `params` (e.g. for inner tree nodes)_
+	pub fn init_params(params: &[u8; 32], out_len: usize, key: &[u8]) -> Self {
+		// Validate the output and key length
+		assert!(out_len <= 32, "Output length is too large");
+		assert!(key.len() <= 32, "Key is too large");
+		
+		// Create Blake2s instance
+		let mut b2 = Self {
+			h: [0; 8], t: [0; 2], f: [0; 2],
+			buf: [0; 64], buf_len: 0,
+			hash_len: out_len, last_node: false,
+			empty: [[0; 8]; 2], precompressed: false
+		};
+		
+		// Xor the parameters with the IV into `b2.h`
+		for ((h, iv), p) in b2.h.iter_mut().zip(IV.iter()).zip(params.chunks(4)) {
+			let mut num = [0; 4];
+			num.copy_from_slice(p);
+			*h = iv ^ u32::from_le_bytes(num);
+		}
+		
+		// Hash the key as `0`-padded 64 byte block
+		if !key.is_empty() {
+			b2.update(key);
+			
+			let pad_len = 64 - key.len();
+			b2.update(&[0; 64][..pad_len]);
+		}
+		
+		b2
+	}
+	
+	/// Loads the 64 byte message `block`
+	fn load(block: &[u8]) -> [u32; 16] {
+		let mut m = [0; 16];
+		for (m, chunk) in m.iter_mut().zip(block.chunks(4)) {
+			let mut num = [0; 4];
+			num.copy_from_slice(chunk);
+			*m = u32::from_le_bytes(num);
+		}
+		m
+	}
+	/// The compression function; increments the counter by `len`, compresses the message block `m` and
+	/// wipes the work arrays
+	fn compress(&mut self, len: u32, mut m: [u32; 16]) {
+		/// G function of compression
+		#[allow(clippy::too_many_arguments)]
+		fn g(r: usize, i: usize, v: &mut[u32], m: &mut[u32], a: usize, b: usize, c: usize, d: usize) {
+			v[a] = add!(v[a], v[b], m[SIGMA[r][2 * i] as usize]);
+			v[d] = (v[d] ^ v[a]).rotate_right(16);
+			v[c] = add!(v[c], v[d]);
+			v[b] = (v[b] ^ v[c]).rotate_right(12);
+			v[a] = add!(v[a], v[b], m[SIGMA[r][2 * i + 1] as usize]);
+			v[d] = (v[d] ^ v[a]).rotate_right(8);
+			v[c] = add!(v[c], v[d]);
+			v[b] = (v[b] ^ v[c]).rotate_right(7);
+		}
+		/// One compression round
+		fn round(r: usize, v: &mut[u32], m: &mut[u32]) {
+			g(r, 0, v, m,  0,  4,  8, 12);
+			g(r, 1, v, m,  1,  5,  9, 13);
+			g(r, 2, v, m,  2,  6, 10, 14);
+			g(r, 3, v, m,  3,  7, 11, 15);
+			g(r, 4, v, m,  0,  5, 10, 15);
+			g(r, 5, v, m,  1,  6, 11, 12);
+			g(r, 6, v, m,  2,  7,  8, 13);
+			g(r, 7, v, m,  3,  4,  9, 14);
+		}
+		
+		// Increment counter
+		self.t[0] = add!(self.t[0], len);
+		if self.t[0] < len { self.t[1] += 1 }
+		
+		// Load v
+		let mut v = [0; 16];
+		v[ 0.. 8].copy_from_slice(&self.h);
+		v[ 8..12].copy_from_slice(&IV[0..4]);
+		v[12] = IV[4] ^ self.t[0];
+		v[13] = IV[5] ^ self.t[1];
+		v[14] = IV[6] ^ self.f[0];
+		v[15] = IV[7] ^ self.f[1];
+		
+		// Do rounds and update state
+		for r in 0..10 { round(r, &mut v, &mut m) }
+		for i in 0..8 { self.h[i] = self.h[i] ^ v[i] ^ v[i + 8] }
+		
+		// Wipe the work arrays
+		wipe(&mut v);
+		wipe(&mut m);
+	}
+	
+	/// Compresses the buffered block ahead if it is complete (e.g. the `0`-padded key block of a
+	/// fresh keyed state); does nothing otherwise
+	///
+	/// _Note: This is used for the initial state of `reset` (see `B2Impl::precompress`)_
+	#[cfg(feature = "std")]
+	pub fn precompress(&mut self) {
+		if self.buf_len != 64 || self.f[0] != 0 { return }
+		
+		// Compute the final chain values without and with the last node flag
+		for last_node in [false, true].iter().copied() {
+			let mut last = self.clone();
+			last.f = [u32::MAX, if last_node { u32::MAX } else { 0 }];
+			last.compress(64, Self::load(&last.buf));
+			self.empty[last_node as usize] = last.h;
+		}
+		
+		// Compress the buffered block
+		self.compress(64, Self::load(&self.buf));
+		wipe(&mut self.buf);
+		self.buf_len = 0;
+		self.precompressed = true;
+	}
+	
+	/// Updates the state with `data`
+	///
+	/// _Note: Full blocks are compressed directly from `data`; only the last (possibly final) block
+	/// is buffered_
+	pub fn update(&mut self, mut data: &[u8]) -> &mut Self {
+		// Discard the precomputed final chain values once data is absorbed
+		if self.precompressed && !data.is_empty() {
+			wipe(&mut self.empty);
+			self.precompressed = false;
+		}
+		
+		// Process data
+		while !data.is_empty() {
+			// Compress the buffered block now that we know that it is not the last block
+			if self.buf_len == 64 {
+				self.compress(64, Self::load(&self.buf));
+				self.buf_len = 0;
+			}
+			
+			// Compress all full blocks except for the last one directly from `data`
+			if self.buf_len == 0 {
+				while data.len() > 64 {
+					self.compress(64, Self::load(&data[..64]));
+					data = &data[64..];
+				}
+			}
+			
+			// Buffer the remaining data
+			let to_copy = min(64 - self.buf_len, data.len());
+			self.buf[self.buf_len .. self.buf_len + to_copy].copy_from_slice(&data[..to_copy]);
+			self.buf_len += to_copy;
+			data = &data[to_copy..];
+		}
+		self
+	}
+	
+	/// Finalizes the state and computes the digest into `buf`
+	pub fn finish(&mut self, buf: &mut[u8]) {
+		// Validate `buf` and the state
+		assert_eq!(buf.len(), self.hash_len, "Invalid buffer length");
+		assert_eq!(self.f[0], 0, "Final hash has already been computed");
+		
+		// Set final block and last node flags
+		self.f[0] = u32::MAX;
+		if self.last_node { self.f[1] = u32::MAX }
+		
+		// `0`-pad the buffer to a complete block length and compress it (unless the last block has
+		// already been compressed ahead)
+		match self.precompressed {
+			true => self.h = self.empty[self.last_node as usize],
+			false => {
+				self.buf[self.buf_len..].iter_mut().for_each(|b| *b = 0);
+				self.compress(self.buf_len as u32, Self::load(&self.buf));
+			}
+		}
+		
+		// Store the entire 256-bit hash in `out`
+		let mut out = [0; 32];
+		for i in 0..8 {
+			let num = self.h[i].to_le_bytes();
+			out[i * 4 .. (i + 1) * 4].copy_from_slice(&num);
+		}
+		buf.copy_from_slice(&out[..buf.len()]);
+		
+		// Wipe everything except for the flags which mark the state as finished
+		wipe(&mut out);
+		wipe(&mut self.h);
+		wipe(&mut self.t);
+		wipe(&mut self.buf);
+		wipe(&mut self.buf_len);
+		wipe(&mut self.empty);
+	}
+	
+	/// Finalizes the state and compares the digest with `tag` in constant time
+	pub fn finish_verify(&mut self, tag: &[u8]) -> Result<(), Blake2Error> {
+		let mut digest = [0; 32];
+		self.finish(&mut digest[..self.hash_len]);
+		let is_valid = ct::eq(&digest[..self.hash_len], tag);
+		wipe(&mut digest);
+		
+		match is_valid {
+			true => Ok(()),
+			false => Err(Blake2Error::InvalidTag)
+		}
+	}
+	
+	/// Marks the state as last node of its tree level
+	#[cfg(feature = "std")]
+	pub fn set_last_node(&mut self, last_node: bool) -> &mut Self {
+		self.last_node = last_node;
+		self
+	}
+	
+	pub fn hash_len(&self) -> usize {
+		self.hash_len
+	}
+}
+impl Drop for B2sImpl {
+	fn drop(&mut self) {
+		wipe(&mut self.h);
+		wipe(&mut self.t);
+		wipe(&mut self.f);
+		wipe(&mut self.buf);
+		wipe(&mut self.buf_len);
+		wipe(&mut self.hash_len);
+		wipe(&mut self.last_node);
+		wipe(&mut self.empty);
+		wipe(&mut self.precompressed);
+	}
+}
\ No newline at end of file
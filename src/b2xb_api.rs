@@ -0,0 +1,156 @@
+use crate::{ Blake2Error, b2b_impl::B2Impl, wipe::wipe };
+use core::cmp::min;
+#[cfg(feature = "std")]
+use std::io::{ self, Read };
+
+
+/// An implementation of the [Blake2Xb](https://blake2.net/blake2x.pdf) extendable-output function
+///
+/// _Note: The output length is part of the parameter block, so different output lengths produce
+/// unrelated outputs. An output length of `u32::MAX` (`2^32 - 1`) is also the specification's
+/// marker for an unknown output length and produces the same output._
+#[derive(Clone)]
+pub struct Blake2Xb {
+	root: B2Impl,
+	params: [u8; 64]
+}
+impl Blake2Xb {
+	/// Creates a new Blake2Xb hash state that produces `xof_len` bytes
+	pub fn new(xof_len: u32) -> Result<Self, Blake2Error> {
+		check_in!(xof_len, 1..=u32::MAX);
+		Ok(Self::init(xof_len, &[]))
+	}
+	/// Creates a new Blake2Xb MAC state that produces `xof_len` bytes using `key`
+	pub fn new_keyed(xof_len: u32, key: &[u8]) -> Result<Self, Blake2Error> {
+		check_in!(xof_len, 1..=u32::MAX);
+		check_in!(key.len(), 1..=64);
+		Ok(Self::init(xof_len, key))
+	}
+	/// Initializes the root state
+	fn init(xof_len: u32, key: &[u8]) -> Self {
+		// Create the root parameters
+		let mut params = [0; 64];
+		params[0] = 64; // Digest length
+		params[1] = key.len() as u8;
+		params[2] = 1; // Fan-out
+		params[3] = 1; // Depth
+		params[12..16].copy_from_slice(&xof_len.to_le_bytes());
+		
+		Self { root: B2Impl::init_params(&params, 64, key), params }
+	}
+	
+	/// Computes the Blake2Xb hash over `data` into `buf` (the output length is `buf.len()`)
+	pub fn hash(buf: &mut[u8], data: &[u8]) -> Result<(), Blake2Error> {
+		check_in!(buf.len(), 1..=u32::MAX as usize);
+		
+		let mut state = Self::new(buf.len() as u32)?;
+		state.update(data);
+		state.finish().squeeze(buf);
+		Ok(())
+	}
+	/// Computes the Blake2Xb MAC over `data` with `key` into `buf` (the output length is `buf.len()`)
+	pub fn auth(buf: &mut[u8], data: &[u8], key: &[u8]) -> Result<(), Blake2Error> {
+		check_in!(buf.len(), 1..=u32::MAX as usize);
+		
+		let mut state = Self::new_keyed(buf.len() as u32, key)?;
+		state.update(data);
+		state.finish().squeeze(buf);
+		Ok(())
+	}
+	
+	/// Updates the state with `data`
+	pub fn update(&mut self, data: &[u8]) -> &mut Self {
+		self.root.update(data);
+		self
+	}
+	/// Finalizes the root hash and returns a reader to squeeze the output
+	pub fn finish(mut self) -> Blake2XbReader {
+		// Compute the root hash
+		let mut root_hash = [0; 64];
+		self.root.finish(&mut root_hash);
+		
+		// Create the expansion parameters
+		let mut params = self.params;
+		params[1] = 0; // Key length
+		params[2] = 0; // Fan-out
+		params[3] = 0; // Depth
+		params[4..8].copy_from_slice(&64u32.to_le_bytes()); // Leaf length
+		params[17] = 64; // Inner length
+		
+		let mut xof_len = [0; 4];
+		xof_len.copy_from_slice(&params[12..16]);
+		let reader = Blake2XbReader {
+			root_hash, params, xof_len: u32::from_le_bytes(xof_len) as u64,
+			pos: 0, block: [0; 64]
+		};
+		wipe(&mut root_hash);
+		reader
+	}
+}
+
+
+/// A reader to squeeze the output of a finalized `Blake2Xb` state
+///
+/// _Note: The root hash and the current output block are wiped on drop_
+#[derive(Clone)]
+pub struct Blake2XbReader {
+	root_hash: [u8; 64],
+	params: [u8; 64],
+	xof_len: u64,
+	
+	pos: u64,
+	block: [u8; 64]
+}
+impl Blake2XbReader {
+	/// Computes the expansion block for the current position into `self.block`
+	fn expand(&mut self) {
+		// Compute the node offset and the block length
+		let offset = self.pos / 64;
+		let block_len = min(self.xof_len - offset * 64, 64) as usize;
+		
+		// Hash the root hash into the block
+		self.params[0] = block_len as u8;
+		self.params[8..12].copy_from_slice(&(offset as u32).to_le_bytes());
+		B2Impl::init_params(&self.params, block_len, &[])
+			.update(&self.root_hash)
+			.finish(&mut self.block[..block_len]);
+	}
+	
+	/// Squeezes the next bytes into `buf` and returns the amount of bytes written
+	///
+	/// _Note: This function writes less than `buf.len()` bytes if the output is exhausted_
+	pub fn squeeze(&mut self, buf: &mut[u8]) -> usize {
+		let mut written = 0;
+		while written < buf.len() && self.pos < self.xof_len {
+			// Compute the next block if we are at a block boundary
+			let block_pos = (self.pos % 64) as usize;
+			if block_pos == 0 { self.expand() }
+			
+			// Copy the bytes from the block
+			let block_len = min(self.xof_len - (self.pos - block_pos as u64), 64) as usize;
+			let to_copy = min(block_len - block_pos, buf.len() - written);
+			buf[written .. written + to_copy].copy_from_slice(&self.block[block_pos .. block_pos + to_copy]);
+			
+			written += to_copy;
+			self.pos += to_copy as u64;
+		}
+		written
+	}
+	
+	/// The amount of bytes that can still be squeezed
+	pub fn remaining(&self) -> u64 {
+		self.xof_len - self.pos
+	}
+}
+#[cfg(feature = "std")]
+impl Read for Blake2XbReader {
+	fn read(&mut self, buf: &mut[u8]) -> io::Result<usize> {
+		Ok(self.squeeze(buf))
+	}
+}
+impl Drop for Blake2XbReader {
+	fn drop(&mut self) {
+		wipe(&mut self.root_hash);
+		wipe(&mut self.block);
+	}
+}
\ No newline at end of file
@@ -0,0 +1,11 @@
+use core::hint;
+
+
+/// Compares `a` and `b` in constant time (only the lengths are compared in variable time)
+pub fn eq(a: &[u8], b: &[u8]) -> bool {
+	if a.len() != b.len() { return false }
+	
+	// Accumulate the differences through `black_box` so that the compiler cannot exit early
+	let diff = a.iter().zip(b.iter()).fold(0, |diff, (a, b)| hint::black_box(diff | (a ^ b)));
+	diff == 0
+}
\ No newline at end of file
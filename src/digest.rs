@@ -0,0 +1,41 @@
+/// Defines the digest type `$name` with up to `$max_len` bytes that compares in constant time
+macro_rules! digest_type {
+	($(#[$doc:meta])* $name:ident, $max_len:expr) => {
+		$(#[$doc])*
+		#[derive(Clone, Copy)]
+		pub struct $name {
+			bytes: [u8; $max_len],
+			len: usize
+		}
+		impl $name {
+			/// The digest bytes
+			pub fn as_slice(&self) -> &[u8] {
+				&self.bytes[..self.len]
+			}
+		}
+		impl core::ops::Deref for $name {
+			type Target = [u8];
+			fn deref(&self) -> &Self::Target {
+				self.as_slice()
+			}
+		}
+		impl AsRef<[u8]> for $name {
+			fn as_ref(&self) -> &[u8] {
+				self.as_slice()
+			}
+		}
+		impl PartialEq for $name {
+			fn eq(&self, other: &Self) -> bool {
+				crate::ct::eq(self.as_slice(), other.as_slice())
+			}
+		}
+		impl Eq for $name {}
+		impl core::fmt::Debug for $name {
+			fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+				write!(f, concat!(stringify!($name), "("))?;
+				for b in self.as_slice() { write!(f, "{:02x}", b)? }
+				write!(f, ")")
+			}
+		}
+	};
+}
@@ -0,0 +1,131 @@
+use crate::{ Blake2Error, b2b_impl::B2Impl, b2s_impl::B2sImpl, hmac_impl::{ HmacHash, HmacImpl } };
+use crypto_api::{
+	rng::{ SecureRng, SecKeyGen },
+	mac::{ MacInfo, Mac, StreamingMac }
+};
+use std::error::Error;
+
+
+/// Implements the HMAC API `$name` with the name `$algorithm` over the hash implementation `$hash`
+macro_rules! hmac_api {
+	($(#[$doc:meta])* $name:ident, $algorithm:expr, $hash:ty) => {
+		$(#[$doc])*
+		#[derive(Default, Clone)]
+		pub struct $name {
+			state: Option<HmacImpl<$hash>>,
+			initial: Option<HmacImpl<$hash>>
+		}
+		impl $name {
+			#[doc = concat!("Creates a `Mac` instance with `", stringify!($name), "` as underlying MAC")]
+			pub fn mac() -> Box<dyn Mac> {
+				Box::new(Self::default())
+			}
+			#[doc = concat!("Creates a `StreamingMac` instance with `", stringify!($name), "` as underlying MAC")]
+			pub fn streaming_mac() -> Box<dyn StreamingMac> {
+				Box::new(Self::default())
+			}
+			
+			/// Computes the MAC of `data` with `key` and compares it with `tag` in constant time (returns
+			/// `Blake2Error::InvalidTag` if the tag does not match)
+			pub fn verify(&self, tag: &[u8], data: &[u8], key: &[u8]) -> Result<(), Blake2Error> {
+				HmacImpl::<$hash>::init(key).update(data).finish_verify(tag)
+			}
+			/// Finalizes the streaming MAC and compares it with `tag` in constant time (returns
+			/// `Blake2Error::InvalidTag` if the tag does not match)
+			pub fn finish_verify(&mut self, tag: &[u8]) -> Result<(), Blake2Error> {
+				let mut state = self.state.take()
+					.ok_or(Blake2Error::ApiMisuse("The MAC is not initialized"))?;
+				state.finish_verify(tag)
+			}
+			
+			/// Resets the streaming MAC to its initial state after the last `init` (i.e. with the same
+			/// key) so that it can be reused after `finish`
+			///
+			/// _Note: The inner and outer padded keys are only compressed once for all resets (see
+			/// `Blake2bState::reset`)_
+			pub fn reset(&mut self) -> Result<(), Blake2Error> {
+				let initial = self.initial.as_mut()
+					.ok_or(Blake2Error::ApiMisuse("The state is not initialized"))?;
+				initial.precompress();
+				self.state = Some(initial.clone());
+				Ok(())
+			}
+			/// Sets `state` as the current and the initial streaming state
+			fn set_state(&mut self, state: HmacImpl<$hash>) {
+				self.initial = Some(state.clone());
+				self.state = Some(state);
+			}
+			
+			/// Returns info about the MAC
+			fn mac_info() -> MacInfo {
+				let mac_len = <$hash as HmacHash>::HASH_LEN;
+				MacInfo { name: $algorithm, is_otm: false, mac_len, mac_len_r: mac_len..(mac_len + 1), key_len_r: 0..usize::MAX }
+			}
+		}
+		
+		impl SecKeyGen for $name {
+			fn new_sec_key(&self, buf: &mut[u8], rng: &mut dyn SecureRng) -> Result<usize, Box<dyn Error + 'static>> {
+				check_in!(buf.len(), 1..=<$hash as HmacHash>::BLOCK_LEN);
+				
+				rng.random(buf)?;
+				Ok(buf.len())
+			}
+		}
+		
+		impl Mac for $name {
+			fn info(&self) -> MacInfo {
+				Self::mac_info()
+			}
+			
+			fn auth(&self, buf: &mut[u8], data: &[u8], key: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+				check_eq!(buf.len(), <$hash as HmacHash>::HASH_LEN);
+				
+				HmacImpl::<$hash>::init(key).update(data).finish(buf);
+				Ok(buf.len())
+			}
+		}
+		
+		impl StreamingMac for $name {
+			fn info(&self) -> MacInfo {
+				Self::mac_info()
+			}
+			
+			fn init(&mut self, key: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
+				self.set_state(HmacImpl::init(key));
+				Ok(())
+			}
+			fn update(&mut self, data: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
+				self.state.as_mut()
+					.ok_or(Blake2Error::ApiMisuse("The MAC is not initialized"))?
+					.update(data);
+				Ok(())
+			}
+			fn finish(&mut self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+				let mut state = self.state.take()
+					.ok_or(Blake2Error::ApiMisuse("The MAC is not initialized"))?;
+				check_eq!(buf.len(), <$hash as HmacHash>::HASH_LEN);
+				
+				state.finish(buf);
+				Ok(buf.len())
+			}
+		}
+	};
+}
+
+
+hmac_api! {
+	/// An implementation of [HMAC](https://tools.ietf.org/html/rfc2104)-Blake2b with the block length
+	/// 128 (e.g. for Noise)
+	///
+	/// _Note: HMAC accepts keys with an arbitrary length and always produces 64 byte MACs; unlike the
+	/// native keyed mode of `Blake2b`, keys that are longer than the block length are hashed_
+	Blake2bHmac, "HMAC-Blake2b", B2Impl
+}
+hmac_api! {
+	/// An implementation of [HMAC](https://tools.ietf.org/html/rfc2104)-Blake2s with the block length
+	/// 64 (e.g. for Noise and WireGuard)
+	///
+	/// _Note: HMAC accepts keys with an arbitrary length and always produces 32 byte MACs; unlike the
+	/// native keyed mode of `Blake2s`, keys that are longer than the block length are hashed_
+	Blake2sHmac, "HMAC-Blake2s", B2sImpl
+}
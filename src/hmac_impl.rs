@@ -0,0 +1,129 @@
+use crate::{ Blake2Error, b2b_impl::B2Impl, b2s_impl::B2sImpl, ct, wipe::wipe };
+
+
+/// The largest block length of all HMAC hashes
+const MAX_BLOCK_LEN: usize = 128;
+/// The largest digest length of all HMAC hashes
+const MAX_HASH_LEN: usize = 64;
+
+
+/// An unkeyed hash with the full digest length that is used by `HmacImpl`
+pub trait HmacHash: Clone {
+	/// The block length
+	const BLOCK_LEN: usize;
+	/// The digest length (= the MAC length)
+	const HASH_LEN: usize;
+	
+	/// Initializes the hash state
+	fn init_hash() -> Self;
+	/// Compresses the buffered block of the hash state ahead (see `B2Impl::precompress`)
+	fn precompress_hash(&mut self);
+	/// Updates the hash state with `data`
+	fn update_hash(&mut self, data: &[u8]);
+	/// Finalizes the hash state and computes the digest into `buf`
+	fn finish_hash(&mut self, buf: &mut[u8]);
+}
+impl HmacHash for B2Impl {
+	const BLOCK_LEN: usize = 128;
+	const HASH_LEN: usize = 64;
+	
+	fn init_hash() -> Self {
+		Self::init(Self::HASH_LEN, &[])
+	}
+	fn precompress_hash(&mut self) {
+		self.precompress();
+	}
+	fn update_hash(&mut self, data: &[u8]) {
+		self.update(data);
+	}
+	fn finish_hash(&mut self, buf: &mut[u8]) {
+		self.finish(buf)
+	}
+}
+impl HmacHash for B2sImpl {
+	const BLOCK_LEN: usize = 64;
+	const HASH_LEN: usize = 32;
+	
+	fn init_hash() -> Self {
+		Self::init(Self::HASH_LEN, &[])
+	}
+	fn precompress_hash(&mut self) {
+		self.precompress();
+	}
+	fn update_hash(&mut self, data: &[u8]) {
+		self.update(data);
+	}
+	fn finish_hash(&mut self, buf: &mut[u8]) {
+		self.finish(buf)
+	}
+}
+
+
+/// An [HMAC](https://tools.ietf.org/html/rfc2104) state over the hash `H` with the real
+/// implementation
+///
+/// _Note: The padded keys are wiped after they have been absorbed_
+#[derive(Clone)]
+pub struct HmacImpl<H: HmacHash> {
+	inner: H,
+	outer: H
+}
+impl<H: HmacHash> HmacImpl<H> {
+	/// Initializes the state with `key` (which is hashed if it is longer than the block length)
+	pub fn init(key: &[u8]) -> Self {
+		// Hash the key if necessary and `0`-pad it to the block length
+		let mut padded = [0; MAX_BLOCK_LEN];
+		let block = &mut padded[..H::BLOCK_LEN];
+		match key.len() {
+			len if len <= H::BLOCK_LEN => block[..len].copy_from_slice(key),
+			_ => {
+				let mut hash = H::init_hash();
+				hash.update_hash(key);
+				hash.finish_hash(&mut block[..H::HASH_LEN]);
+			}
+		}
+		
+		// Absorb the inner and the outer padded key
+		let (mut inner, mut outer) = (H::init_hash(), H::init_hash());
+		block.iter_mut().for_each(|b| *b ^= 0x36);
+		inner.update_hash(block);
+		block.iter_mut().for_each(|b| *b ^= 0x36 ^ 0x5c);
+		outer.update_hash(block);
+		
+		wipe(&mut padded);
+		Self { inner, outer }
+	}
+	
+	/// Compresses the buffered inner and outer padded keys ahead if nothing has been absorbed yet (see
+	/// `B2Impl::precompress`)
+	pub fn precompress(&mut self) {
+		self.inner.precompress_hash();
+		self.outer.precompress_hash();
+	}
+	
+	/// Updates the state with `data`
+	pub fn update(&mut self, data: &[u8]) -> &mut Self {
+		self.inner.update_hash(data);
+		self
+	}
+	/// Finalizes the state and computes the MAC with the digest length of `H` into `buf`
+	pub fn finish(&mut self, buf: &mut[u8]) {
+		let mut digest = [0; MAX_HASH_LEN];
+		self.inner.finish_hash(&mut digest[..H::HASH_LEN]);
+		self.outer.update_hash(&digest[..H::HASH_LEN]);
+		self.outer.finish_hash(buf);
+		wipe(&mut digest);
+	}
+	/// Finalizes the state and compares the MAC with `tag` in constant time
+	pub fn finish_verify(&mut self, tag: &[u8]) -> Result<(), Blake2Error> {
+		let mut mac = [0; MAX_HASH_LEN];
+		self.finish(&mut mac[..H::HASH_LEN]);
+		let is_valid = ct::eq(&mac[..H::HASH_LEN], tag);
+		wipe(&mut mac);
+		
+		match is_valid {
+			true => Ok(()),
+			false => Err(Blake2Error::InvalidTag)
+		}
+	}
+}
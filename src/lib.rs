@@ -1,23 +1,82 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+
+/// Checks if `$v` is in the range `$r`
+macro_rules! check_in {
+    ($v:expr, $r:expr) => ({
+    	if $v < *$r.start() {
+    		Err(Blake2Error::ApiMisuse(concat!("`", stringify!($v), "` is too small")))?
+    	}
+    	if $v > *$r.end() {
+    		Err(Blake2Error::ApiMisuse(concat!("`", stringify!($v), "` is too large")))?
+    	}
+    });
+}
+/// Checks if `$v` is equal to `$e`
+macro_rules! check_eq {
+    ($v:expr, $e:expr) => ({
+    	if $v != $e {
+    		Err(Blake2Error::ApiMisuse(concat!("`", stringify!($v), "` is invalid")))?
+    	}
+    })
+}
+
+
+#[macro_use] mod digest;
 mod b2b_impl;
-mod b2b_api;
+mod b2b_backend;
+mod b2b_const;
+mod b2b_hasher;
+mod b2b_key;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))] mod b2b_x86;
+mod b2b_multi;
+mod b2b_params;
+mod b2b_state;
+mod b2s_impl;
+mod b2s_hasher;
+mod b2xb_api;
+mod ct;
+mod wipe;
+#[cfg(feature = "std")] mod b2b_api;
+#[cfg(feature = "std")] mod b2b_tree;
+#[cfg(feature = "std")] mod b2s_api;
+#[cfg(feature = "std")] mod hmac_impl;
+#[cfg(feature = "std")] mod hmac_api;
+#[cfg(feature = "std")] mod parallel_impl;
+#[cfg(feature = "std")] mod parallel_api;
 
-pub use crate::b2b_api::Blake2b;
-pub use crypto_api;
-use std::{
-	error::Error,
-	fmt::{ Display, Formatter, Result as FmtResult }
+pub use crate::{
+	b2b_backend::Blake2bBackend, b2b_const::Blake2bConst,
+	b2b_hasher::{ Blake2b160, Blake2b256, Blake2b384, Blake2b512, Blake2bDigest, Blake2bFixed, Blake2bHasher },
+	b2b_key::Blake2bKey, b2b_multi::{ Blake2bJob, Blake2bMulti },
+	b2b_params::Blake2bParams, b2b_state::Blake2bState, b2s_hasher::{ Blake2sDigest, Blake2sHasher },
+	b2xb_api::{ Blake2Xb, Blake2XbReader }
 };
+#[cfg(feature = "std")]
+pub use crate::{
+	b2b_api::Blake2b, b2b_tree::Blake2bTree, b2s_api::Blake2s, hmac_api::{ Blake2bHmac, Blake2sHmac },
+	parallel_api::{ Blake2bp, Blake2sp }
+};
+#[cfg(feature = "std")]
+pub use crypto_api;
+// Exported for the integration tests only; this is not part of the public API
+#[doc(hidden)]
+pub use crate::wipe::{ wipe, Zeroable };
+use core::fmt::{ Display, Formatter, Result as FmtResult };
 
 
 /// A Blake2 related error
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum Blake2Error {
 	/// An API misuse happened
-	ApiMisuse(&'static str)
+	ApiMisuse(&'static str),
+	/// The MAC tag does not match the data and key
+	InvalidTag
 }
 impl Display for Blake2Error {
 	fn fmt(&self, f: &mut Formatter) -> FmtResult {
 		write!(f, "{:?}", self)
 	}
 }
-impl Error for Blake2Error {}
\ No newline at end of file
+#[cfg(feature = "std")]
+impl std::error::Error for Blake2Error {}
\ No newline at end of file
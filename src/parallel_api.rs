@@ -0,0 +1,222 @@
+use crate::{ Blake2Error, parallel_impl::{ B2bpImpl, B2spImpl } };
+use crypto_api::{
+	rng::{ SecureRng, SecKeyGen },
+	mac::{ MacInfo, Mac, VarlenMac, StreamingMac, StreamingVarlenMac },
+	hash::{ HashInfo, Hash, VarlenHash, StreamingHash, StreamingVarlenHash }
+};
+use std::error::Error;
+
+
+/// Implements the parallel hash API `$name` with the name `$algorithm` over the state `$impl` with the
+/// maximal digest and key length `$max_len`
+macro_rules! parallel_api {
+	($(#[$doc:meta])* $name:ident, $algorithm:expr, $impl:ident, $max_len:expr) => {
+		$(#[$doc])*
+		#[derive(Default, Clone)]
+		pub struct $name {
+			state: Option<$impl>,
+			initial: Option<$impl>
+		}
+		impl $name {
+			#[doc = concat!("Creates a `Hash` instance with `", stringify!($name), "` as underlying hash")]
+			pub fn hash() -> Box<dyn Hash> {
+				Box::new(Self::default())
+			}
+			#[doc = concat!("Creates a `VarlenHash` instance with `", stringify!($name), "` as underlying hash")]
+			pub fn varlen_hash() -> Box<dyn VarlenHash> {
+				Box::new(Self::default())
+			}
+			#[doc = concat!("Creates a `Mac` instance with `", stringify!($name), "` as underlying MAC")]
+			pub fn mac() -> Box<dyn Mac> {
+				Box::new(Self::default())
+			}
+			#[doc = concat!("Creates a `VarlenMac` instance with `", stringify!($name), "` as underlying MAC")]
+			pub fn varlen_mac() -> Box<dyn VarlenMac> {
+				Box::new(Self::default())
+			}
+			
+			#[doc = concat!("Creates a `StreamingHash` instance with `", stringify!($name), "` as underlying hash")]
+			pub fn streaming_hash() -> Box<dyn StreamingHash> {
+				Box::new(Self::default())
+			}
+			#[doc = concat!("Creates a `StreamingVarlenHash` instance with `", stringify!($name), "` as underlying hash")]
+			pub fn streaming_varlen_hash() -> Box<dyn StreamingVarlenHash> {
+				Box::new(Self::default())
+			}
+			#[doc = concat!("Creates a `StreamingMac` instance with `", stringify!($name), "` as underlying MAC")]
+			pub fn streaming_mac() -> Box<dyn StreamingMac> {
+				Box::new(Self::default())
+			}
+			#[doc = concat!("Creates a `StreamingVarlenMac` instance with `", stringify!($name), "` as underlying MAC")]
+			pub fn streaming_varlen_mac() -> Box<dyn StreamingVarlenMac> {
+				Box::new(Self::default())
+			}
+			
+			/// Resets the streaming hash or MAC to its initial state after the last `init` (i.e. with the same
+			/// digest length and key) so that it can be reused after `finish`
+			///
+			/// _Note: The key blocks of the leaves are only compressed once for all resets (see
+			/// `Blake2bState::reset`)_
+			pub fn reset(&mut self) -> Result<(), Blake2Error> {
+				let initial = self.initial.as_mut()
+					.ok_or(Blake2Error::ApiMisuse("The state is not initialized"))?;
+				initial.precompress();
+				self.state = Some(initial.clone());
+				Ok(())
+			}
+			/// Sets `state` as the current and the initial streaming state
+			fn set_state(&mut self, state: $impl) {
+				self.initial = Some(state.clone());
+				self.state = Some(state);
+			}
+			
+			/// Returns info about the hash
+			fn hash_info() -> HashInfo {
+				HashInfo { name: $algorithm, hash_len: $max_len, hash_len_r: 1..($max_len + 1) }
+			}
+			/// Returns info about the MAC
+			fn mac_info() -> MacInfo {
+				MacInfo { name: $algorithm, is_otm: false, mac_len: $max_len, mac_len_r: 1..($max_len + 1), key_len_r: 1..($max_len + 1) }
+			}
+		}
+		
+		impl SecKeyGen for $name {
+			fn new_sec_key(&self, buf: &mut[u8], rng: &mut dyn SecureRng) -> Result<usize, Box<dyn Error + 'static>> {
+				check_in!(buf.len(), 1..=$max_len);
+				
+				rng.random(buf)?;
+				Ok(buf.len())
+			}
+		}
+		
+		impl Hash for $name {
+			fn info(&self) -> HashInfo {
+				Self::hash_info()
+			}
+			
+			fn hash(&self, buf: &mut[u8], data: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+				check_eq!(buf.len(), $max_len);
+				self.varlen_hash(buf, data)
+			}
+		}
+		impl VarlenHash for $name {
+			fn varlen_hash(&self, buf: &mut[u8], data: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+				check_in!(buf.len(), 1..=$max_len);
+				
+				$impl::init(buf.len()).update_all(data).finish(buf);
+				Ok(buf.len())
+			}
+		}
+		
+		impl Mac for $name {
+			fn info(&self) -> MacInfo {
+				Self::mac_info()
+			}
+			
+			fn auth(&self, buf: &mut[u8], data: &[u8], key: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+				check_eq!(buf.len(), $max_len);
+				self.varlen_auth(buf, data, key)
+			}
+		}
+		impl VarlenMac for $name {
+			fn varlen_auth(&self, buf: &mut[u8], data: &[u8], key: &[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+				check_in!(buf.len(), 1..=$max_len);
+				check_in!(key.len(), 1..=$max_len);
+				
+				$impl::init_mac(buf.len(), key).update_all(data).finish(buf);
+				Ok(buf.len())
+			}
+		}
+		
+		impl StreamingHash for $name {
+			fn info(&self) -> HashInfo {
+				let mut info = Self::hash_info();
+				if let Some(s) = self.state.as_ref() { info.hash_len = s.hash_len() }
+				info
+			}
+			
+			fn init(&mut self) -> Result<(), Box<dyn Error + 'static>> {
+				self.set_state($impl::init($max_len));
+				Ok(())
+			}
+			fn update(&mut self, input: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
+				self.state.as_mut()
+					.ok_or(Blake2Error::ApiMisuse("The hash is not initialized"))?
+					.update(input);
+				Ok(())
+			}
+			fn finish(&mut self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+				let mut state = self.state.take()
+					.ok_or(Blake2Error::ApiMisuse("The hash is not initialized"))?;
+				check_eq!(buf.len(), state.hash_len());
+				
+				state.finish(buf);
+				Ok(buf.len())
+			}
+		}
+		impl StreamingVarlenHash for $name {
+			fn varlen_init(&mut self, hash_len: usize) -> Result<(), Box<dyn Error + 'static>> {
+				check_in!(hash_len, 1..=$max_len);
+				
+				self.set_state($impl::init(hash_len));
+				Ok(())
+			}
+		}
+		
+		impl StreamingMac for $name {
+			fn info(&self) -> MacInfo {
+				let mut info = Self::mac_info();
+				if let Some(s) = self.state.as_ref() { info.mac_len = s.hash_len() }
+				info
+			}
+			
+			fn init(&mut self, key: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
+				check_in!(key.len(), 1..=$max_len);
+				
+				self.set_state($impl::init_mac($max_len, key));
+				Ok(())
+			}
+			fn update(&mut self, data: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
+				self.state.as_mut()
+					.ok_or(Blake2Error::ApiMisuse("The MAC is not initialized"))?
+					.update(data);
+				Ok(())
+			}
+			fn finish(&mut self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+				let mut state = self.state.take()
+					.ok_or(Blake2Error::ApiMisuse("The MAC is not initialized"))?;
+				check_eq!(buf.len(), state.hash_len());
+				
+				state.finish(buf);
+				Ok(buf.len())
+			}
+		}
+		impl StreamingVarlenMac for $name {
+			fn varlen_init(&mut self, mac_len: usize, key: &[u8]) -> Result<(), Box<dyn Error + 'static>> {
+				check_in!(mac_len, 1..=$max_len);
+				check_in!(key.len(), 1..=$max_len);
+				
+				self.set_state($impl::init_mac(mac_len, key));
+				Ok(())
+			}
+		}
+	};
+}
+
+
+parallel_api! {
+	/// An implementation of the 4-way parallel [Blake2bp](https://blake2.net/blake2.pdf)
+	///
+	/// _Note: The oneshot hashes and MACs process the 4 leaves in parallel threads if the input is at
+	/// least 256 KiB large (the threads are spawned once per call); streaming updates are always
+	/// processed on the calling thread to avoid spawning threads for every chunk_
+	Blake2bp, "Blake2bp", B2bpImpl, 64
+}
+parallel_api! {
+	/// An implementation of the 8-way parallel [Blake2sp](https://blake2.net/blake2.pdf)
+	///
+	/// _Note: The oneshot hashes and MACs process the 8 leaves in parallel threads if the input is at
+	/// least 256 KiB large (the threads are spawned once per call); streaming updates are always
+	/// processed on the calling thread to avoid spawning threads for every chunk_
+	Blake2sp, "Blake2sp", B2spImpl, 32
+}
\ No newline at end of file
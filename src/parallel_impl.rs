@@ -0,0 +1,248 @@
+use crate::{ b2b_impl::B2Impl, b2s_impl::B2sImpl, wipe::wipe };
+use std::{ array, cmp::min, thread };
+
+
+/// The length of a stripe that contains one block for each leaf
+const STRIPE_LEN: usize = 512;
+/// The largest digest length of all leaf hashes
+const MAX_HASH_LEN: usize = 64;
+/// The minimum length of a oneshot input to process the leaves in parallel threads
+const THREAD_THRESHOLD: usize = 256 * 1024;
+
+
+/// A hash core that can be used for the nodes of a parallel tree with depth 2
+pub trait ParallelNode: Clone + Send {
+	/// The block length
+	const BLOCK_LEN: usize;
+	/// The full digest length (= the inner length of the tree)
+	const HASH_LEN: usize;
+	
+	/// Initializes the leaf at `node_offset` of a tree with `fan_out` leaves, the digest length
+	/// `digest_len` and `key`
+	fn init_leaf(fan_out: usize, digest_len: usize, key: &[u8], node_offset: usize) -> Self;
+	/// Initializes the root of a tree with `fan_out` leaves, the digest length `digest_len` and the
+	/// key length `key_len` of the leaves
+	fn init_root(fan_out: usize, digest_len: usize, key_len: usize) -> Self;
+	/// Compresses the buffered key block of the node ahead (see `B2Impl::precompress`)
+	fn precompress_node(&mut self);
+	/// Updates the node with `data`
+	fn update_node(&mut self, data: &[u8]);
+	/// Finalizes the node and computes the digest into `buf`
+	fn finish_node(&mut self, buf: &mut[u8]);
+}
+impl ParallelNode for B2Impl {
+	const BLOCK_LEN: usize = 128;
+	const HASH_LEN: usize = 64;
+	
+	fn init_leaf(fan_out: usize, digest_len: usize, key: &[u8], node_offset: usize) -> Self {
+		let mut p = b2b_tree_params(fan_out, digest_len, key.len());
+		p[8] = node_offset as u8;
+		
+		let mut leaf = Self::init_params(&p, Self::HASH_LEN, key);
+		leaf.set_last_node(node_offset == fan_out - 1);
+		leaf
+	}
+	fn init_root(fan_out: usize, digest_len: usize, key_len: usize) -> Self {
+		let mut p = b2b_tree_params(fan_out, digest_len, key_len);
+		p[16] = 1; // Node depth
+		
+		let mut root = Self::init_params(&p, digest_len, &[]);
+		root.set_last_node(true);
+		root
+	}
+	fn precompress_node(&mut self) {
+		self.precompress();
+	}
+	fn update_node(&mut self, data: &[u8]) {
+		self.update(data);
+	}
+	fn finish_node(&mut self, buf: &mut[u8]) {
+		self.finish(buf)
+	}
+}
+impl ParallelNode for B2sImpl {
+	const BLOCK_LEN: usize = 64;
+	const HASH_LEN: usize = 32;
+	
+	fn init_leaf(fan_out: usize, digest_len: usize, key: &[u8], node_offset: usize) -> Self {
+		let mut p = b2s_tree_params(fan_out, digest_len, key.len());
+		p[8] = node_offset as u8;
+		
+		let mut leaf = Self::init_params(&p, Self::HASH_LEN, key);
+		leaf.set_last_node(node_offset == fan_out - 1);
+		leaf
+	}
+	fn init_root(fan_out: usize, digest_len: usize, key_len: usize) -> Self {
+		let mut p = b2s_tree_params(fan_out, digest_len, key_len);
+		p[14] = 1; // Node depth
+		
+		let mut root = Self::init_params(&p, digest_len, &[]);
+		root.set_last_node(true);
+		root
+	}
+	fn precompress_node(&mut self) {
+		self.precompress();
+	}
+	fn update_node(&mut self, data: &[u8]) {
+		self.update(data);
+	}
+	fn finish_node(&mut self, buf: &mut[u8]) {
+		self.finish(buf)
+	}
+}
+
+
+/// Creates the common Blake2b parameter block of all nodes of a tree with `fan_out` leaves
+fn b2b_tree_params(fan_out: usize, digest_len: usize, key_len: usize) -> [u8; 64] {
+	let mut p = [0; 64];
+	p[0] = digest_len as u8;
+	p[1] = key_len as u8;
+	p[2] = fan_out as u8;
+	p[3] = 2; // Depth
+	p[17] = 64; // Inner length
+	p
+}
+/// Creates the common Blake2s parameter block of all nodes of a tree with `fan_out` leaves
+fn b2s_tree_params(fan_out: usize, digest_len: usize, key_len: usize) -> [u8; 32] {
+	let mut p = [0; 32];
+	p[0] = digest_len as u8;
+	p[1] = key_len as u8;
+	p[2] = fan_out as u8;
+	p[3] = 2; // Depth
+	p[15] = 32; // Inner length
+	p
+}
+
+
+/// A Blake2bp state with the real implementation
+pub type B2bpImpl = ParallelImpl<B2Impl, 4>;
+/// A Blake2sp state with the real implementation
+pub type B2spImpl = ParallelImpl<B2sImpl, 8>;
+
+
+/// A state of the parallel mode with `LEAVES` leaves over the hash `H` with the real implementation
+///
+/// _Note: The buffer is wiped on drop and once the final hash has been computed_
+#[derive(Clone)]
+pub struct ParallelImpl<H: ParallelNode, const LEAVES: usize> {
+	leaves: [H; LEAVES],
+	root: H,
+	
+	buf: [u8; STRIPE_LEN],
+	buf_len: usize,
+	hash_len: usize
+}
+impl<H: ParallelNode, const LEAVES: usize> ParallelImpl<H, LEAVES> {
+	/// The block length of the leaves (fails the compilation if the leaves don't fill a stripe)
+	const BLOCK_LEN: usize = {
+		assert!(LEAVES * H::BLOCK_LEN == STRIPE_LEN, "The leaves must fill a stripe");
+		H::BLOCK_LEN
+	};
+	
+	/// Initializes the state as hash with `out_len`
+	pub fn init(out_len: usize) -> Self {
+		Self::init_mac(out_len, &[])
+	}
+	/// Initializes the state as MAC with `out_len` and `key`
+	pub fn init_mac(out_len: usize, key: &[u8]) -> Self {
+		// Create the keyed leaves with their node offset and the root
+		let leaves = array::from_fn(|i| H::init_leaf(LEAVES, out_len, key, i));
+		let root = H::init_root(LEAVES, out_len, key.len());
+		Self { leaves, root, buf: [0; STRIPE_LEN], buf_len: 0, hash_len: out_len }
+	}
+	
+	/// Compresses the buffered key blocks of the leaves ahead if nothing has been absorbed yet (see
+	/// `B2Impl::precompress`)
+	pub fn precompress(&mut self) {
+		if self.buf_len != 0 { return }
+		self.leaves.iter_mut().for_each(H::precompress_node);
+	}
+	
+	/// Absorbs all complete stripes in `data` into `leaf` with index `i`
+	fn absorb_stripes(leaf: &mut H, i: usize, data: &[u8]) {
+		for stripe in data.chunks_exact(STRIPE_LEN) {
+			leaf.update_node(&stripe[i * Self::BLOCK_LEN .. (i + 1) * Self::BLOCK_LEN]);
+		}
+	}
+	
+	/// Updates the state with `data` on the calling thread
+	pub fn update(&mut self, data: &[u8]) -> &mut Self {
+		self.absorb(data, false)
+	}
+	/// Updates the state with the entire message `data` and processes the leaves in parallel threads
+	/// if `data` is large enough
+	///
+	/// _Note: This is meant for oneshot operations so that the threads are only spawned once per
+	/// hash; streaming updates use `update` to avoid spawning threads for every chunk_
+	pub fn update_all(&mut self, data: &[u8]) -> &mut Self {
+		self.absorb(data, data.len() >= THREAD_THRESHOLD)
+	}
+	/// Updates the state with `data` and processes the leaves in parallel threads if `threaded` is set
+	fn absorb(&mut self, mut data: &[u8], threaded: bool) -> &mut Self {
+		// Fill the buffer and distribute it to the leaves if it is complete
+		if self.buf_len > 0 {
+			let to_copy = min(STRIPE_LEN - self.buf_len, data.len());
+			self.buf[self.buf_len .. self.buf_len + to_copy].copy_from_slice(&data[..to_copy]);
+			self.buf_len += to_copy;
+			data = &data[to_copy..];
+			
+			if self.buf_len == STRIPE_LEN {
+				for (i, leaf) in self.leaves.iter_mut().enumerate() {
+					Self::absorb_stripes(leaf, i, &self.buf);
+				}
+				self.buf_len = 0;
+			}
+		}
+		
+		// Distribute all complete stripes to the leaves (in parallel if requested)
+		let (stripes, rest) = data.split_at(data.len() - data.len() % STRIPE_LEN);
+		match threaded {
+			true => thread::scope(|scope| {
+				for (i, leaf) in self.leaves.iter_mut().enumerate() {
+					scope.spawn(move || Self::absorb_stripes(leaf, i, stripes));
+				}
+			}),
+			false => for (i, leaf) in self.leaves.iter_mut().enumerate() {
+				Self::absorb_stripes(leaf, i, stripes);
+			}
+		}
+		
+		// Buffer the remaining bytes
+		self.buf[self.buf_len .. self.buf_len + rest.len()].copy_from_slice(rest);
+		self.buf_len += rest.len();
+		self
+	}
+	
+	/// Finalizes the state and computes the digest into `buf`
+	pub fn finish(&mut self, buf: &mut[u8]) {
+		// Validate `buf`
+		assert_eq!(buf.len(), self.hash_len, "Invalid buffer length");
+		
+		// Absorb the remaining bytes, finalize the leaves and absorb their digests into the root
+		for (i, leaf) in self.leaves.iter_mut().enumerate() {
+			let start = min(i * Self::BLOCK_LEN, self.buf_len);
+			let end = min(start + Self::BLOCK_LEN, self.buf_len);
+			leaf.update_node(&self.buf[start..end]);
+			
+			let mut digest = [0; MAX_HASH_LEN];
+			leaf.finish_node(&mut digest[..H::HASH_LEN]);
+			self.root.update_node(&digest[..H::HASH_LEN]);
+			wipe(&mut digest);
+		}
+		self.root.finish_node(buf);
+		
+		// Wipe the buffer
+		wipe(&mut self.buf);
+		wipe(&mut self.buf_len);
+	}
+	
+	pub fn hash_len(&self) -> usize {
+		self.hash_len
+	}
+}
+impl<H: ParallelNode, const LEAVES: usize> Drop for ParallelImpl<H, LEAVES> {
+	fn drop(&mut self) {
+		wipe(&mut self.buf);
+		wipe(&mut self.buf_len);
+	}
+}
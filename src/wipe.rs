@@ -0,0 +1,42 @@
+use core::{ mem, ptr, sync::atomic::{ self, Ordering::SeqCst } };
+
+
+/// A type for which all zero bytes are a valid value (i.e. integers, booleans, SIMD vectors and
+/// arrays of them)
+///
+/// _Note: This trait is only exported (hidden) for the integration tests and is not part of the
+/// public API_
+///
+/// # Safety
+/// All zero bytes must be a valid value of the implementing type
+pub unsafe trait Zeroable: Copy {}
+unsafe impl Zeroable for bool {}
+unsafe impl Zeroable for u8 {}
+unsafe impl Zeroable for u32 {}
+unsafe impl Zeroable for u64 {}
+unsafe impl Zeroable for usize {}
+unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {}
+#[cfg(target_arch = "x86")]
+unsafe impl Zeroable for core::arch::x86::__m128i {}
+#[cfg(target_arch = "x86")]
+unsafe impl Zeroable for core::arch::x86::__m256i {}
+#[cfg(target_arch = "x86")]
+unsafe impl Zeroable for core::arch::x86::__m512i {}
+#[cfg(target_arch = "x86_64")]
+unsafe impl Zeroable for core::arch::x86_64::__m128i {}
+#[cfg(target_arch = "x86_64")]
+unsafe impl Zeroable for core::arch::x86_64::__m256i {}
+#[cfg(target_arch = "x86_64")]
+unsafe impl Zeroable for core::arch::x86_64::__m512i {}
+
+/// Overwrites `value` with zero bytes using a volatile write so that the compiler cannot elide it
+pub fn wipe<T: Zeroable>(value: &mut T) {
+	// Safety: The pointer is valid and all zero bytes are a valid value for `Zeroable` types
+	unsafe { ptr::write_volatile(value, mem::zeroed()) };
+	atomic::compiler_fence(SeqCst);
+}
+/// Overwrites all elements of `slice` with zero bytes (see `wipe`)
+#[cfg(feature = "std")]
+pub fn wipe_slice<T: Zeroable>(slice: &mut[T]) {
+	slice.iter_mut().for_each(wipe);
+}
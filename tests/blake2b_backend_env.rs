@@ -0,0 +1,26 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{ Blake2b, Blake2bBackend };
+use std::env;
+
+
+#[test]
+fn test() {
+	// Test the backend names
+	for backend in Blake2bBackend::ALL.iter().copied() {
+		assert_eq!(Blake2bBackend::from_name(backend.name()).unwrap(), backend);
+	}
+	assert!(Blake2bBackend::from_name("avx1024").is_err());
+	
+	// Select the portable backend before the first use
+	// _Note: This is the only test in this binary, so the backend has not been selected yet_
+	assert_eq!(Blake2bBackend::from_env(), Ok(None));
+	env::set_var("CRYPTO_API_BLAKE2_BACKEND", "portable");
+	assert_eq!(Blake2bBackend::from_env(), Ok(Some(Blake2bBackend::Portable)));
+	assert_eq!(Blake2bBackend::current(), Blake2bBackend::Portable);
+	
+	// Hash the empty input with the selected backend
+	let mut buf = [0; 64];
+	Blake2b::hash().hash(&mut buf, b"").unwrap();
+	assert_eq!(buf[..8], [0x78, 0x6a, 0x02, 0xf7, 0x42, 0x01, 0x59, 0x03]);
+}
\ No newline at end of file
@@ -0,0 +1,19 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{ Blake2Error, Blake2b, Blake2bBackend };
+use std::env;
+
+
+#[test]
+fn test() {
+	// Name an unknown backend before the first use
+	// _Note: This is the only test in this binary, so the backend has not been selected yet_
+	env::set_var("CRYPTO_API_BLAKE2_BACKEND", "avx1024");
+	assert_eq!(Blake2bBackend::from_env(), Err(Blake2Error::ApiMisuse("Unknown backend name")));
+	assert_eq!(Blake2bBackend::current(), Blake2bBackend::detect());
+	
+	// Hash the empty input with the detected backend
+	let mut buf = [0; 64];
+	Blake2b::hash().hash(&mut buf, b"").unwrap();
+	assert_eq!(buf[..8], [0x78, 0x6a, 0x02, 0xf7, 0x42, 0x01, 0x59, 0x03]);
+}
@@ -0,0 +1,239 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{ Blake2Xb, Blake2b, Blake2bBackend, Blake2bJob, Blake2bMulti, Blake2bParams, Blake2bp };
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+struct HashVector {
+	line: usize,
+	input_: Vec<u8>,
+	output: Vec<u8>
+}
+#[derive(Debug)]
+struct MacVector {
+	line: usize,
+	key__: Vec<u8>,
+	input: Vec<u8>,
+	mac__: Vec<u8>
+}
+#[derive(Debug)]
+struct KdfVector {
+	line: usize,
+	base_key: Vec<u8>,
+	salt____: Vec<u8>,
+	info____: Vec<u8>,
+	key_____: Vec<u8>
+}
+#[derive(Debug)]
+struct PersonalVector {
+	line: usize,
+	personal: Vec<u8>,
+	key_____: Vec<u8>,
+	input___: Vec<u8>,
+	output__: Vec<u8>
+}
+#[derive(Debug)]
+struct StreamingHashVector {
+	line: usize,
+	input0: Vec<u8>,
+	input1: Vec<u8>,
+	input2: Vec<u8>,
+	input3: Vec<u8>,
+	output: Vec<u8>
+}
+#[derive(Debug)]
+struct StreamingMacVector {
+	line: usize,
+	key___: Vec<u8>,
+	input0: Vec<u8>,
+	input1: Vec<u8>,
+	input2: Vec<u8>,
+	mac___: Vec<u8>
+}
+#[derive(Debug)]
+struct ParamsVector {
+	line: usize,
+	hash_len___: usize,
+	key________: Vec<u8>,
+	salt_______: Vec<u8>,
+	personal___: Vec<u8>,
+	fanout_____: usize,
+	depth______: usize,
+	leaf_len___: usize,
+	node_offset: u64,
+	node_depth_: usize,
+	inner_len__: usize,
+	last_node__: usize,
+	input______: Vec<u8>,
+	output_____: Vec<u8>
+}
+#[derive(Debug)]
+struct TreeVector {
+	line: usize,
+	hash_len_: usize,
+	key______: Vec<u8>,
+	salt_____: Vec<u8>,
+	personal_: Vec<u8>,
+	fanout___: usize,
+	depth____: usize,
+	leaf_len_: usize,
+	inner_len: usize,
+	chunk_len: usize,
+	input____: Vec<u8>,
+	output___: Vec<u8>
+}
+#[derive(Debug)]
+struct XofVector {
+	line: usize,
+	key___: Vec<u8>,
+	input_: Vec<u8>,
+	output: Vec<u8>
+}
+
+
+/// Tests all Blake2b-based test vectors with the currently selected backend
+fn test_vectors(backend: Blake2bBackend) {
+	// Hash and MAC
+	let hash = Blake2b::varlen_hash();
+	for v in read_test_vectors!("blake2b_hash.txt" => HashVector{ line, input_, output }) {
+		let mut buf = vec![0; v.output.len()];
+		hash.varlen_hash(&mut buf, &v.input_).unwrap();
+		assert_eq!(buf, v.output, "{:?} @{} failed", backend, v.line);
+	}
+	let mac = Blake2b::varlen_mac();
+	for v in read_test_vectors!("blake2b_mac.txt" => MacVector{ line, key__, input, mac__ }) {
+		let mut buf = vec![0; v.mac__.len()];
+		mac.varlen_auth(&mut buf, &v.input, &v.key__).unwrap();
+		assert_eq!(buf, v.mac__, "{:?} @{} failed", backend, v.line);
+	}
+	
+	// KDF
+	let kdf = Blake2b::kdf();
+	for v in read_test_vectors!("blake2b_kdf.txt" => KdfVector{ line, base_key, salt____, info____, key_____ }) {
+		let mut buf = vec![0; v.key_____.len()];
+		kdf.derive(&mut buf, &v.base_key, &v.salt____, &v.info____).unwrap();
+		assert_eq!(buf, v.key_____, "{:?} @{} failed", backend, v.line);
+	}
+	
+	// Personalization
+	let vectors = read_test_vectors!(
+		"blake2b_personal.txt" => PersonalVector{ line, personal, key_____, input___, output__ }
+	);
+	for v in vectors {
+		let mut state = Blake2bParams::new();
+		state.hash_len(v.output__.len()).key(&v.key_____).personal(&v.personal);
+		
+		let mut buf = vec![0; v.output__.len()];
+		state.to_state().unwrap().update(&v.input___).finish(&mut buf).unwrap();
+		assert_eq!(buf, v.output__, "{:?} @{} failed", backend, v.line);
+	}
+	
+	// Streaming hash and MAC
+	let vectors = read_test_vectors!(
+		"blake2b_streaming_hash.txt" => StreamingHashVector{ line, input0, input1, input2, input3, output }
+	);
+	for v in vectors {
+		let mut state = Blake2bParams::new().hash_len(v.output.len()).to_state().unwrap();
+		state.update(&v.input0).update(&v.input1).update(&v.input2).update(&v.input3);
+		
+		let mut buf = vec![0; v.output.len()];
+		state.finish(&mut buf).unwrap();
+		assert_eq!(buf, v.output, "{:?} @{} failed", backend, v.line);
+	}
+	let vectors = read_test_vectors!(
+		"blake2b_streaming_mac.txt" => StreamingMacVector{ line, key___, input0, input1, input2, mac___ }
+	);
+	for v in vectors {
+		let mut state = Blake2bParams::new().hash_len(v.mac___.len()).key(&v.key___).to_state().unwrap();
+		state.update(&v.input0).update(&v.input1).update(&v.input2);
+		
+		let mut buf = vec![0; v.mac___.len()];
+		state.finish(&mut buf).unwrap();
+		assert_eq!(buf, v.mac___, "{:?} @{} failed", backend, v.line);
+	}
+	
+	// Parameter block and tree
+	let vectors = read_test_vectors!(
+		"blake2b_params.txt" => ParamsVector {
+			line, hash_len___, key________, salt_______, personal___, fanout_____, depth______,
+			leaf_len___, node_offset, node_depth_, inner_len__, last_node__, input______, output_____
+		}
+	);
+	for v in vectors {
+		let mut state = Blake2bParams::new()
+			.hash_len(v.hash_len___).key(&v.key________).salt(&v.salt_______).personal(&v.personal___)
+			.fanout(v.fanout_____ as u8).depth(v.depth______ as u8).leaf_len(v.leaf_len___ as u32)
+			.node_offset(v.node_offset).node_depth(v.node_depth_ as u8).inner_len(v.inner_len__)
+			.last_node(v.last_node__ != 0)
+			.to_state().unwrap();
+		
+		let mut buf = vec![0; v.hash_len___];
+		state.update(&v.input______).finish(&mut buf).unwrap();
+		assert_eq!(buf, v.output_____, "{:?} @{} failed", backend, v.line);
+	}
+	let vectors = read_test_vectors!(
+		"blake2b_tree.txt" => TreeVector {
+			line, hash_len_, key______, salt_____, personal_, fanout___, depth____, leaf_len_,
+			inner_len, chunk_len, input____, output___
+		}
+	);
+	for v in vectors {
+		let mut tree = Blake2bParams::new()
+			.hash_len(v.hash_len_).key(&v.key______).salt(&v.salt_____).personal(&v.personal_)
+			.fanout(v.fanout___ as u8).depth(v.depth____ as u8).leaf_len(v.leaf_len_ as u32)
+			.inner_len(v.inner_len)
+			.to_tree().unwrap();
+		for leaf in v.input____.chunks(v.chunk_len) { tree.add_leaf(leaf).unwrap(); }
+		
+		let mut buf = vec![0; v.hash_len_];
+		tree.finish(&mut buf).unwrap();
+		assert_eq!(buf, v.output___, "{:?} @{} failed", backend, v.line);
+	}
+	
+	// Blake2bp
+	let hash = Blake2bp::varlen_hash();
+	for v in read_test_vectors!("blake2bp_hash.txt" => HashVector{ line, input_, output }) {
+		let mut buf = vec![0; v.output.len()];
+		hash.varlen_hash(&mut buf, &v.input_).unwrap();
+		assert_eq!(buf, v.output, "{:?} @{} failed", backend, v.line);
+	}
+	let mac = Blake2bp::varlen_mac();
+	for v in read_test_vectors!("blake2bp_mac.txt" => MacVector{ line, key__, input, mac__ }) {
+		let mut buf = vec![0; v.mac__.len()];
+		mac.varlen_auth(&mut buf, &v.input, &v.key__).unwrap();
+		assert_eq!(buf, v.mac__, "{:?} @{} failed", backend, v.line);
+	}
+	
+	// Blake2Xb
+	for v in read_test_vectors!("blake2xb.txt" => XofVector{ line, key___, input_, output }) {
+		let mut buf = vec![0; v.output.len()];
+		match v.key___.len() {
+			0 => Blake2Xb::hash(&mut buf, &v.input_).unwrap(),
+			_ => Blake2Xb::auth(&mut buf, &v.input_, &v.key___).unwrap()
+		}
+		assert_eq!(buf, v.output, "{:?} @{} failed", backend, v.line);
+	}
+	
+	// Multi-buffer
+	let vectors = read_test_vectors!("blake2b_mac.txt" => MacVector{ line, key__, input, mac__ });
+	let mut bufs: Vec<Vec<u8>> = vectors.iter().map(|v| vec![0; v.mac__.len()]).collect();
+	let mut jobs: Vec<Blake2bJob> = bufs.iter_mut().zip(vectors.iter())
+		.map(|(buf, v)| Blake2bJob::new_keyed(buf, &v.input, &v.key__))
+		.collect();
+	Blake2bMulti::hash(&mut jobs).unwrap();
+	for (buf, v) in bufs.iter().zip(vectors.iter()) {
+		assert_eq!(buf, &v.mac__, "{:?} @{} failed", backend, v.line);
+	}
+}
+#[test]
+fn test() {
+	// Test all vectors with each available backend
+	// _Note: This is the only test in this binary, so no other test observes the forced backends_
+	for backend in Blake2bBackend::ALL.iter().copied().filter(|b| b.is_available()) {
+		Blake2bBackend::force(backend).unwrap();
+		assert_eq!(Blake2bBackend::current(), backend);
+		test_vectors(backend);
+	}
+	Blake2bBackend::force(Blake2bBackend::detect()).unwrap();
+}
\ No newline at end of file
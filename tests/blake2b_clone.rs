@@ -0,0 +1,128 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{
+	Blake2Xb, Blake2b, Blake2bHasher, Blake2bParams, Blake2bp, Blake2s,
+	crypto_api::{ hash::StreamingHash, mac::StreamingMac }
+};
+
+
+/// A common prefix that spans multiple blocks
+const PREFIX: &[u8] = &[0x2a; 300];
+/// The suffixes to hash after the prefix
+const SUFFIXES: &[&[u8]] = &[b"", b"Testolope", &[0x17; 129]];
+
+
+/// Concatenates the prefix and `suffix`
+fn message(suffix: &[u8]) -> Vec<u8> {
+	[PREFIX, suffix].concat()
+}
+
+
+#[test]
+fn test_state() {
+	// Absorb the prefix once
+	let mut prefix = Blake2bParams::new().hash_len(32).key(b"Testolope").to_state().unwrap();
+	prefix.update(PREFIX);
+	
+	for suffix in SUFFIXES {
+		let mut forked = [0; 32];
+		prefix.clone().update(suffix).finish(&mut forked).unwrap();
+		
+		let mut expected = [0; 32];
+		Blake2b::varlen_mac().varlen_auth(&mut expected, &message(suffix), b"Testolope").unwrap();
+		assert_eq!(forked, expected);
+	}
+}
+
+
+#[test]
+fn test_hasher() {
+	let mut prefix = Blake2bHasher::new(64).unwrap();
+	prefix.update(PREFIX);
+	
+	for suffix in SUFFIXES {
+		let mut forked = prefix.clone();
+		forked.update(suffix);
+		
+		let mut expected = [0; 64];
+		Blake2b::hash().hash(&mut expected, &message(suffix)).unwrap();
+		assert_eq!(forked.finalize().as_slice(), &expected[..]);
+	}
+}
+
+
+#[test]
+fn test_streaming_traits() {
+	// Fork a streaming hash
+	let mut prefix = Blake2b::default();
+	StreamingHash::init(&mut prefix).unwrap();
+	StreamingHash::update(&mut prefix, PREFIX).unwrap();
+	
+	for suffix in SUFFIXES {
+		let mut forked = prefix.clone();
+		StreamingHash::update(&mut forked, suffix).unwrap();
+		
+		let (mut buf, mut expected) = ([0; 64], [0; 64]);
+		StreamingHash::finish(&mut forked, &mut buf).unwrap();
+		Blake2b::hash().hash(&mut expected, &message(suffix)).unwrap();
+		assert_eq!(buf, expected);
+	}
+	
+	// Fork a streaming MAC
+	let mut prefix = Blake2b::default();
+	StreamingMac::init(&mut prefix, b"Testolope").unwrap();
+	StreamingMac::update(&mut prefix, PREFIX).unwrap();
+	
+	for suffix in SUFFIXES {
+		let mut forked = prefix.clone();
+		StreamingMac::update(&mut forked, suffix).unwrap();
+		
+		let (mut buf, mut expected) = ([0; 64], [0; 64]);
+		StreamingMac::finish(&mut forked, &mut buf).unwrap();
+		Blake2b::mac().auth(&mut expected, &message(suffix), b"Testolope").unwrap();
+		assert_eq!(buf, expected);
+	}
+	
+	// Ensure that the original state is still usable
+	let mut buf = [0; 64];
+	StreamingMac::finish(&mut prefix, &mut buf).unwrap();
+}
+
+
+#[test]
+fn test_other_states() {
+	// Blake2s and Blake2bp
+	let (mut b2s, mut b2bp) = (Blake2s::default(), Blake2bp::default());
+	StreamingHash::init(&mut b2s).unwrap();
+	StreamingHash::update(&mut b2s, PREFIX).unwrap();
+	StreamingHash::init(&mut b2bp).unwrap();
+	StreamingHash::update(&mut b2bp, PREFIX).unwrap();
+	
+	for suffix in SUFFIXES {
+		let (mut forked, mut expected) = ([0; 32], [0; 32]);
+		let mut b2s = b2s.clone();
+		StreamingHash::update(&mut b2s, suffix).unwrap();
+		StreamingHash::finish(&mut b2s, &mut forked).unwrap();
+		Blake2s::hash().hash(&mut expected, &message(suffix)).unwrap();
+		assert_eq!(forked, expected);
+		
+		let (mut forked, mut expected) = ([0; 64], [0; 64]);
+		let mut b2bp = b2bp.clone();
+		StreamingHash::update(&mut b2bp, suffix).unwrap();
+		StreamingHash::finish(&mut b2bp, &mut forked).unwrap();
+		Blake2bp::hash().hash(&mut expected, &message(suffix)).unwrap();
+		assert_eq!(forked, expected);
+	}
+	
+	// Blake2Xb
+	let mut prefix = Blake2Xb::new(200).unwrap();
+	prefix.update(PREFIX);
+	for suffix in SUFFIXES {
+		let (mut forked, mut expected) = ([0; 200], [0; 200]);
+		let mut state = prefix.clone();
+		state.update(suffix);
+		state.finish().squeeze(&mut forked);
+		Blake2Xb::hash(&mut expected, &message(suffix)).unwrap();
+		assert_eq!(forked[..], expected[..]);
+	}
+}
\ No newline at end of file
@@ -0,0 +1,85 @@
+use crypto_api_blake2::Blake2bConst;
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+struct HashVector {
+	line: usize,
+	input_: Vec<u8>,
+	output: Vec<u8>
+}
+#[derive(Debug)]
+struct MacVector {
+	line: usize,
+	key__: Vec<u8>,
+	input: Vec<u8>,
+	mac__: Vec<u8>
+}
+
+
+/// Evaluates the const implementation at runtime for the digest length `buf.len()`
+fn auth(buf: &mut[u8], data: &[u8], key: &[u8]) {
+	/// Dispatches the runtime digest length to the const generic digest length
+	macro_rules! dispatch {
+		($($n:literal)*) => ({
+			match buf.len() {
+				$($n => buf.copy_from_slice(&Blake2bConst::auth::<$n>(data, key)),)*
+				len => panic!("Unsupported digest length {}", len)
+			}
+		});
+	}
+	dispatch!(1 4 16 20 28 32 48 64)
+}
+
+
+#[test]
+fn test() {
+	// Test the KAT and custom vectors (using the runtime evaluation of the const implementation)
+	for v in read_test_vectors!("blake2b_hash.txt" => HashVector{ line, input_, output }) {
+		let mut buf = vec![0; v.output.len()];
+		auth(&mut buf, &v.input_, &[]);
+		assert_eq!(buf, v.output, "@{} failed", v.line);
+	}
+	for v in read_test_vectors!("blake2b_mac.txt" => MacVector{ line, key__, input, mac__ }) {
+		let mut buf = vec![0; v.mac__.len()];
+		auth(&mut buf, &v.input, &v.key__);
+		assert_eq!(buf, v.mac__, "@{} failed", v.line);
+	}
+}
+
+
+#[test]
+#[cfg(feature = "std")]
+fn test_compile_time() {
+	use crypto_api_blake2::Blake2b;
+	
+	// Digests that are evaluated at compile time
+	const EMPTY: [u8; 64] = Blake2bConst::hash(b"");
+	const TESTOLOPE: [u8; 32] = Blake2bConst::hash(b"Testolope");
+	const KEYED: [u8; 16] = Blake2bConst::auth(&[0x2a; 300], b"Testolope");
+	
+	// Compare the compile-time digests with the runtime varlen hash and MAC
+	let mut buf = vec![0; 64];
+	Blake2b::varlen_hash().varlen_hash(&mut buf, b"").unwrap();
+	assert_eq!(buf, EMPTY);
+	
+	let mut buf = vec![0; 32];
+	Blake2b::varlen_hash().varlen_hash(&mut buf, b"Testolope").unwrap();
+	assert_eq!(buf, TESTOLOPE);
+	
+	let mut buf = vec![0; 16];
+	Blake2b::varlen_mac().varlen_auth(&mut buf, &[0x2a; 300], b"Testolope").unwrap();
+	assert_eq!(buf, KEYED);
+}
+
+
+#[test]
+#[should_panic(expected = "The digest length is invalid")]
+fn test_api_hash_len() {
+	Blake2bConst::hash::<65>(b"Testolope");
+}
+#[test]
+#[should_panic(expected = "The key is too large")]
+fn test_api_key_len() {
+	Blake2bConst::auth::<64>(b"Testolope", &[0; 65]);
+}
\ No newline at end of file
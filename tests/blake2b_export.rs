@@ -0,0 +1,118 @@
+use crypto_api_blake2::{ Blake2Error, Blake2bHasher, Blake2bParams, Blake2bState };
+
+
+/// The data to hash
+fn data() -> Vec<u8> {
+	(0..1000).map(|b| (b * 7) as u8).collect()
+}
+/// Creates a keyed and personalized state with the last node flag
+fn state() -> Blake2bState {
+	let mut params = Blake2bParams::new();
+	params.hash_len(48).key(b"Testolope").personal(b"Personal").last_node(true);
+	params.to_state().unwrap()
+}
+/// Updates the corruption check of the exported state `bytes` after modifying it
+fn update_check(bytes: &mut[u8]) {
+	let mut hasher = Blake2bHasher::new(16).unwrap();
+	hasher.update(&bytes[..bytes.len() - 16]);
+	
+	let check_offset = bytes.len() - 16;
+	bytes[check_offset..].copy_from_slice(&hasher.finalize());
+}
+
+
+#[test]
+fn test() {
+	// Compute the uninterrupted hash
+	let data = data();
+	let mut expected = [0; 48];
+	state().update(&data).finish(&mut expected).unwrap();
+	
+	// Interrupt the hash at different positions and resume it from the exported state
+	for split in [0, 1, 127, 128, 129, 256, 300, 999, 1000].iter().copied() {
+		let exported = state().update(&data[..split]).export().unwrap();
+		
+		let mut buf = [0; 48];
+		let mut second = Blake2bState::import(&exported).unwrap();
+		second.update(&data[split..]).finish(&mut buf).unwrap();
+		assert_eq!(buf, expected, "Split at {} failed", split);
+		
+		// The imported state is the initial state
+		second.reset().update(&data[split..]).finish(&mut buf).unwrap();
+		assert_eq!(buf, expected, "Split at {} failed", split);
+	}
+}
+
+
+#[test]
+#[cfg(feature = "std")]
+fn test_streaming() {
+	use crypto_api_blake2::{ Blake2b, crypto_api::mac::StreamingMac };
+	
+	let data = data();
+	let mut expected = [0; 64];
+	Blake2b::mac().auth(&mut expected, &data, b"Testolope").unwrap();
+	
+	// Interrupt and resume a streaming MAC
+	let mut first = Blake2b::default();
+	StreamingMac::init(&mut first, b"Testolope").unwrap();
+	StreamingMac::update(&mut first, &data[..333]).unwrap();
+	
+	let mut buf = [0; 64];
+	let mut second = Blake2b::import(&first.export().unwrap()).unwrap();
+	StreamingMac::update(&mut second, &data[333..]).unwrap();
+	StreamingMac::finish(&mut second, &mut buf).unwrap();
+	assert_eq!(buf, expected);
+	
+	// Export an uninitialized or finalized state
+	assert_eq!(Blake2b::default().export().err(), Some(Blake2Error::ApiMisuse("The state is not initialized")));
+	assert_eq!(second.export().err(), Some(Blake2Error::ApiMisuse("The state is not initialized")));
+}
+
+
+#[test]
+fn test_format() {
+	let mut state = Blake2bParams::new().to_state().unwrap();
+	let exported = state.update(b"Testolope").export().unwrap();
+	
+	// Magic, version, digest length, buffer length, flags and buffer
+	assert_eq!(exported.len(), Blake2bState::EXPORT_LEN);
+	assert_eq!(exported[..8], *b"B2bS\x02\x40\x09\x00");
+	assert_eq!(exported[104..113], *b"Testolope");
+}
+
+
+#[test]
+fn test_api() {
+	/// Modifies an exported state with `modify` and returns the import error
+	fn import(update_check: bool, modify: impl FnOnce(&mut Vec<u8>)) -> Option<Blake2Error> {
+		let mut exported = state().update(b"Testolope").export().unwrap().to_vec();
+		modify(&mut exported);
+		if update_check { self::update_check(&mut exported) }
+		Blake2bState::import(&exported).err()
+	}
+	let error = |desc| Some(Blake2Error::ApiMisuse(desc));
+	
+	// Invalid length, format, version (including the previous version `1`) and corruption check
+	assert_eq!(import(false, |b| { b.pop(); }), error("`bytes.len()` is invalid"));
+	assert_eq!(import(false, |b| b[0] = b'X'), error("The state has an invalid format"));
+	assert_eq!(import(false, |b| b[4] = 1), error("The state version is not supported"));
+	assert_eq!(import(false, |b| b[4] = 3), error("The state version is not supported"));
+	assert_eq!(import(false, |b| b[8] ^= 1), error("The state is corrupted"));
+	
+	// Invalid fields with a valid corruption check
+	assert_eq!(import(true, |_| ()), None);
+	assert_eq!(import(true, |b| b[5] = 0), error("The state is invalid"));
+	assert_eq!(import(true, |b| b[5] = 65), error("The state is invalid"));
+	assert_eq!(import(true, |b| b[6] = 129), error("The state is invalid"));
+	assert_eq!(import(true, |b| b[7] = 2), error("The state is invalid"));
+	assert_eq!(import(true, |b| b[72] = 1), error("The state is invalid"));
+	assert_eq!(import(true, |b| b[88] = 0xff), error("The state is invalid"));
+	assert_eq!(import(true, |b| b[231] = 1), error("The state is invalid"));
+	assert_eq!(import(true, |b| { b[72] = 128; b[6] = 0 }), error("The state is invalid"));
+	
+	// Export a finalized state
+	let mut state = state();
+	state.finish(&mut [0; 48]).unwrap();
+	assert_eq!(state.export().err(), error("The state has already been finalized"));
+}
\ No newline at end of file
@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use crypto_api_blake2::{ Blake2Error, Blake2b };
 include!("read_test_vectors.rs");
 
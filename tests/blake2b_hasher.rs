@@ -0,0 +1,110 @@
+use crypto_api_blake2::{ Blake2Error, Blake2b160, Blake2b256, Blake2b384, Blake2b512, Blake2bFixed, Blake2bHasher };
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+struct HashVector {
+	line: usize,
+	input_: Vec<u8>,
+	output: Vec<u8>
+}
+#[derive(Debug)]
+struct MacVector {
+	line: usize,
+	key__: Vec<u8>,
+	input: Vec<u8>,
+	mac__: Vec<u8>
+}
+
+
+#[test]
+fn test() {
+	// Test the hash vectors
+	for v in read_test_vectors!("blake2b_hash.txt" => HashVector{ line, input_, output }) {
+		let mut hasher = Blake2bHasher::new(v.output.len()).unwrap();
+		for chunk in v.input_.chunks(100) { hasher.update(chunk); }
+		assert_eq!(hasher.finalize().as_slice(), v.output.as_slice(), "@{} failed", v.line);
+		
+		match v.output.len() {
+			64 => assert_eq!(Blake2b512::hash(&v.input_).as_ref(), v.output.as_slice(), "@{} failed", v.line),
+			48 => assert_eq!(Blake2b384::hash(&v.input_).as_ref(), v.output.as_slice(), "@{} failed", v.line),
+			_ => ()
+		}
+	}
+	
+	// Test the MAC vectors
+	for v in read_test_vectors!("blake2b_mac.txt" => MacVector{ line, key__, input, mac__ }) {
+		let mut hasher = Blake2bHasher::new_keyed(v.mac__.len(), &v.key__).unwrap();
+		hasher.update(&v.input);
+		assert_eq!(&*hasher.finalize(), v.mac__.as_slice(), "@{} failed", v.line);
+		
+		if v.mac__.len() == 32 {
+			let mac = Blake2b256::auth(&v.input, &v.key__).unwrap();
+			assert_eq!(mac.as_ref(), v.mac__.as_slice(), "@{} failed", v.line);
+		}
+	}
+}
+#[test]
+fn test_fixed() {
+	// Compare the fixed-length states with the runtime-length states
+	let data: Vec<u8> = (0..1000).map(|b| b as u8).collect();
+	let mut hasher = Blake2bHasher::new(20).unwrap();
+	hasher.update(&data);
+	assert_eq!(Blake2b160::hash(&data).as_ref(), hasher.finalize().as_slice());
+	
+	let mut hasher = Blake2bHasher::new_keyed(7, b"Testolope").unwrap();
+	hasher.update(&data);
+	let mut fixed = Blake2bFixed::<7>::new_keyed(b"Testolope").unwrap();
+	for chunk in data.chunks(129) { fixed.update(chunk); }
+	assert_eq!(fixed.finalize().as_ref(), hasher.finalize().as_slice());
+}
+#[test]
+fn test_digest_eq() {
+	// Compare digests with the same and different contents and lengths
+	let digest = |len: usize, data: &[u8]| {
+		let mut hasher = Blake2bHasher::new(len).unwrap();
+		hasher.update(data);
+		hasher.finalize()
+	};
+	assert_eq!(digest(32, b"Testolope"), digest(32, b"Testolope"));
+	assert_ne!(digest(32, b"Testolope"), digest(32, b"Testolopf"));
+	assert_ne!(digest(32, b"Testolope"), digest(33, b"Testolope"));
+}
+
+
+#[derive(Debug)]
+struct ApiTestVector {
+	line: usize,
+	hash_len__: usize,
+	key_len___: usize,
+	error_desc: &'static str
+}
+impl ApiTestVector {
+	fn test(&self) {
+		// Create the invalid state and compare the error
+		let result = match self.key_len___ {
+			0 => Blake2bHasher::new(self.hash_len__),
+			key_len => Blake2bHasher::new_keyed(self.hash_len__, &vec![0; key_len])
+		};
+		match result {
+			Err(Blake2Error::ApiMisuse(desc)) => assert_eq!(
+				desc, self.error_desc,
+				"Invalid API-error description @{}", self.line
+			),
+			_ => panic!("Invalid result returned @{}", self.line)
+		}
+	}
+}
+#[test]
+fn test_api() {
+	// Read test vectors
+	let vectors: Vec<ApiTestVector> = read_test_vectors!(
+		"blake2b_hasher_api.txt" => ApiTestVector{ line, hash_len__, key_len___, error_desc }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+	
+	// Test the fixed-length states (invalid digest lengths fail the compilation)
+	assert_eq!(Blake2b512::new_keyed(&[]).err(), Some(Blake2Error::ApiMisuse("`key.len()` is too small")));
+	assert_eq!(Blake2b512::auth(b"", &[0; 65]).err(), Some(Blake2Error::ApiMisuse("`key.len()` is too large")));
+}
\ No newline at end of file
@@ -0,0 +1,175 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{
+	Blake2Error, Blake2b, Blake2bHmac,
+	crypto_api::mac::StreamingMac
+};
+include!("read_test_vectors.rs");
+
+
+/// Computes HMAC-Blake2b of `data` with `key` as specified in RFC 2104 using the plain hash
+fn rfc2104(key: &[u8], data: &[u8]) -> Vec<u8> {
+	let hash = |data: &[u8]| {
+		let mut buf = vec![0; 64];
+		Blake2b::hash().hash(&mut buf, data).unwrap();
+		buf
+	};
+	
+	// Hash long keys and `0`-pad the key to the block length
+	let mut key = match key.len() {
+		len if len > 128 => hash(key),
+		_ => key.to_vec()
+	};
+	key.resize(128, 0);
+	
+	// H((K ^ opad) || H((K ^ ipad) || data))
+	let ipad: Vec<u8> = key.iter().map(|b| b ^ 0x36).collect();
+	let opad: Vec<u8> = key.iter().map(|b| b ^ 0x5c).collect();
+	let inner = hash(&[&ipad[..], data].concat());
+	hash(&[&opad[..], &inner[..]].concat())
+}
+
+
+#[derive(Debug)]
+pub struct TestVector {
+	line: usize,
+	key__: Vec<u8>,
+	input: Vec<u8>,
+	mac__: Vec<u8>
+}
+impl TestVector {
+	pub fn test(&self) {
+		// One-shot
+		let mut buf = vec![0; 64];
+		Blake2bHmac::mac().auth(&mut buf, &self.input, &self.key__).unwrap();
+		assert_eq!(buf, self.mac__, "@{} failed", self.line);
+		assert_eq!(rfc2104(&self.key__, &self.input), self.mac__, "@{} failed", self.line);
+		
+		// Streaming
+		let mut mac = Blake2bHmac::streaming_mac();
+		mac.init(&self.key__).unwrap();
+		for chunk in self.input.chunks(7) { mac.update(chunk).unwrap() }
+		mac.finish(&mut buf).unwrap();
+		assert_eq!(buf, self.mac__, "@{} failed", self.line);
+	}
+}
+#[test]
+fn test() {
+	// Read test vectors
+	let vectors: Vec<TestVector> = read_test_vectors!(
+		"blake2b_hmac.txt"
+			=> TestVector{ line, key__, input, mac__ }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
+
+
+#[test]
+fn test_construction() {
+	// Test the RFC 2104 construction around the block length and with multi-block inputs
+	for key_len in [0, 1, 64 - 1, 64, 128 - 1, 128, 128 + 1, 2 * 128 + 1, 1000].iter() {
+		let key: Vec<u8> = (0..*key_len).map(|i| i as u8).collect();
+		for data_len in [0, 1, 128, 128 + 1, 3 * 128].iter() {
+			let data = vec![0x2a; *data_len];
+			
+			let mut buf = vec![0; 64];
+			Blake2bHmac::mac().auth(&mut buf, &data, &key).unwrap();
+			assert_eq!(buf, rfc2104(&key, &data), "Invalid MAC for key length {}", key_len);
+		}
+	}
+}
+
+
+#[derive(Debug)]
+pub struct HkdfTestVector {
+	line: usize,
+	ck_____: Vec<u8>,
+	ikm____: Vec<u8>,
+	output1: Vec<u8>,
+	output2: Vec<u8>,
+	output3: Vec<u8>
+}
+impl HkdfTestVector {
+	pub fn test(&self) {
+		// `HKDF(chaining_key, input_key_material, 3)` as specified by the Noise protocol framework
+		let hmac = |key: &[u8], data: &[u8]| {
+			let mut buf = vec![0; 64];
+			Blake2bHmac::mac().auth(&mut buf, data, key).unwrap();
+			buf
+		};
+		let temp_key = hmac(&self.ck_____, &self.ikm____);
+		let output1 = hmac(&temp_key, &[0x01]);
+		let output2 = hmac(&temp_key, &[&output1[..], &[0x02]].concat());
+		let output3 = hmac(&temp_key, &[&output2[..], &[0x03]].concat());
+		
+		assert_eq!(output1, self.output1, "@{} failed", self.line);
+		assert_eq!(output2, self.output2, "@{} failed", self.line);
+		assert_eq!(output3, self.output3, "@{} failed", self.line);
+		
+		// Verify the outputs
+		let mac = Blake2bHmac::default();
+		mac.verify(&self.output1, &[0x01], &temp_key).unwrap();
+		mac.verify(&self.output3, &[&self.output2[..], &[0x03]].concat(), &temp_key).unwrap();
+	}
+}
+#[test]
+fn test_noise_hkdf() {
+	// Read test vectors
+	let vectors: Vec<HkdfTestVector> = read_test_vectors!(
+		"blake2b_noise_hkdf.txt"
+			=> HkdfTestVector{ line, ck_____, ikm____, output1, output2, output3 }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
+
+
+#[test]
+fn test_verify() {
+	let mut tag = vec![0; 64];
+	Blake2bHmac::mac().auth(&mut tag, b"Testolope", b"Testolope").unwrap();
+	
+	// One-shot
+	let mac = Blake2bHmac::default();
+	assert_eq!(mac.verify(&tag, b"Testolope", b"Testolope"), Ok(()));
+	assert_eq!(mac.verify(&tag, b"Testolopf", b"Testolope"), Err(Blake2Error::InvalidTag));
+	assert_eq!(mac.verify(&tag, b"Testolope", b"Testolopf"), Err(Blake2Error::InvalidTag));
+	assert_eq!(mac.verify(&tag[..64 - 1], b"Testolope", b"Testolope"), Err(Blake2Error::InvalidTag));
+	
+	// Streaming
+	let mut mac = Blake2bHmac::default();
+	mac.init(b"Testolope").unwrap();
+	mac.update(b"Testolope").unwrap();
+	assert_eq!(mac.finish_verify(&tag), Ok(()));
+	assert_eq!(mac.finish_verify(&tag), Err(Blake2Error::ApiMisuse("The MAC is not initialized")));
+	
+	mac.reset().unwrap();
+	mac.update(b"Testolopf").unwrap();
+	assert_eq!(mac.finish_verify(&tag), Err(Blake2Error::InvalidTag));
+}
+
+
+#[test]
+fn test_api() {
+	// Invalid MAC length
+	let mut buf = vec![0; 64 - 1];
+	let err = Blake2bHmac::mac().auth(&mut buf, b"Testolope", b"Testolope").unwrap_err();
+	assert_eq!(err.downcast_ref::<Blake2Error>(), Some(&Blake2Error::ApiMisuse("`buf.len()` is invalid")));
+	
+	// Uninitialized streaming MAC
+	let mut mac = Blake2bHmac::default();
+	let err = mac.update(b"Testolope").unwrap_err();
+	assert_eq!(err.downcast_ref::<Blake2Error>(), Some(&Blake2Error::ApiMisuse("The MAC is not initialized")));
+	assert_eq!(mac.reset().err(), Some(Blake2Error::ApiMisuse("The state is not initialized")));
+	
+	// Reset after finish
+	let (mut first, mut second) = (vec![0; 64], vec![0; 64]);
+	mac.init(b"Testolope").unwrap();
+	mac.update(b"Testolope").unwrap();
+	mac.finish(&mut first).unwrap();
+	mac.reset().unwrap();
+	mac.update(b"Testolope").unwrap();
+	mac.finish(&mut second).unwrap();
+	assert_eq!(first, second);
+}
\ No newline at end of file
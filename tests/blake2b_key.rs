@@ -0,0 +1,135 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{
+	Blake2Error, Blake2Xb, Blake2b, Blake2bHasher, Blake2bJob, Blake2bKey, Blake2bMulti,
+	Blake2bParams, Blake2bp, crypto_api::rng::SecureRng
+};
+use std::{ error::Error, mem::{ self, MaybeUninit }, ptr, slice };
+
+
+/// A deterministic "RNG" that counts up from `0`
+struct CountingRng(u8);
+impl SecureRng for CountingRng {
+	fn random(&mut self, buf: &mut[u8]) -> Result<(), Box<dyn Error + 'static>> {
+		for b in buf.iter_mut() {
+			*b = self.0;
+			self.0 = self.0.wrapping_add(1);
+		}
+		Ok(())
+	}
+}
+/// An RNG that always fails
+struct FailingRng;
+impl SecureRng for FailingRng {
+	fn random(&mut self, _buf: &mut[u8]) -> Result<(), Box<dyn Error + 'static>> {
+		Err(Blake2Error::ApiMisuse("The RNG failed"))?
+	}
+}
+
+
+#[test]
+fn test_new() {
+	assert_eq!(Blake2bKey::new(&[0x17]).unwrap().as_slice(), &[0x17]);
+	assert_eq!(Blake2bKey::new(&[0x17; 64]).unwrap().len(), 64);
+	
+	assert_eq!(Blake2bKey::new(&[]).err(), Some(Blake2Error::ApiMisuse("`key.len()` is too small")));
+	assert_eq!(Blake2bKey::new(&[0; 65]).err(), Some(Blake2Error::ApiMisuse("`key.len()` is too large")));
+}
+
+
+#[test]
+fn test_generate() {
+	let key = Blake2bKey::generate(32, &mut CountingRng(0)).unwrap();
+	assert_eq!(key.as_slice(), (0..32).collect::<Vec<u8>>().as_slice());
+	
+	// Invalid lengths and RNG errors
+	for (len, desc) in [(0, "`len` is too small"), (65, "`len` is too large"), (32, "The RNG failed")].iter() {
+		let err = match *len {
+			32 => Blake2bKey::generate(*len, &mut FailingRng).unwrap_err(),
+			_ => Blake2bKey::generate(*len, &mut CountingRng(0)).unwrap_err()
+		};
+		assert_eq!(err.downcast_ref::<Blake2Error>(), Some(&Blake2Error::ApiMisuse(desc)));
+	}
+}
+
+
+#[test]
+fn test_debug() {
+	let key = Blake2bKey::new(b"Testolope").unwrap();
+	assert_eq!(format!("{:?}", key), "Blake2bKey(<9 bytes redacted>)");
+}
+
+
+#[test]
+fn test_eq() {
+	let key = Blake2bKey::new(b"Testolope").unwrap();
+	assert_eq!(key, key.clone());
+	assert_ne!(key, Blake2bKey::new(b"Testolopf").unwrap());
+	assert_ne!(key, Blake2bKey::new(b"Testolop").unwrap());
+	assert_ne!(key, Blake2bKey::new(b"Testolope\0").unwrap());
+}
+
+
+#[test]
+fn test_keyed_apis() {
+	let key = Blake2bKey::new(b"Testolope").unwrap();
+	let raw: &[u8] = b"Testolope";
+	let data = [0x2a; 300];
+	
+	// `crypto_api` MAC and KDF
+	let (mut expected, mut mac) = ([0; 64], [0; 64]);
+	Blake2b::mac().auth(&mut expected, &data, raw).unwrap();
+	Blake2b::mac().auth(&mut mac, &data, &key).unwrap();
+	assert_eq!(mac[..], expected[..]);
+	
+	let mut streaming = Blake2b::streaming_mac();
+	streaming.init(&key).unwrap();
+	streaming.update(&data).unwrap();
+	streaming.finish(&mut mac).unwrap();
+	assert_eq!(mac[..], expected[..]);
+	Blake2b::default().verify(&expected, &data, &key).unwrap();
+	
+	let (mut expected, mut derived) = ([0; 32], [0; 32]);
+	Blake2b::kdf().derive(&mut expected, raw, b"Salt", b"Info").unwrap();
+	Blake2b::kdf().derive(&mut derived, &key, b"Salt", b"Info").unwrap();
+	assert_eq!(derived, expected);
+	
+	let (mut expected, mut mac) = ([0; 64], [0; 64]);
+	Blake2bp::mac().auth(&mut expected, &data, raw).unwrap();
+	Blake2bp::mac().auth(&mut mac, &data, &key).unwrap();
+	assert_eq!(mac[..], expected[..]);
+	
+	// Statically dispatched states, parameter blocks, Blake2Xb and multi-buffer jobs
+	let mut expected = Blake2bHasher::new_keyed(32, raw).unwrap();
+	expected.update(&data);
+	let expected = expected.finalize();
+	
+	let mut hasher = Blake2bHasher::new_keyed(32, &key).unwrap();
+	hasher.update(&data);
+	assert_eq!(hasher.finalize(), expected);
+	
+	let mut state = Blake2bParams::new().hash_len(32).key(&key).to_state().unwrap();
+	let mut digest = [0; 32];
+	state.update(&data).finish(&mut digest).unwrap();
+	assert_eq!(&digest[..], expected.as_slice());
+	
+	let mut job_digest = [0; 32];
+	Blake2bMulti::hash(&mut [Blake2bJob::new_keyed(&mut job_digest, &data, &key)]).unwrap();
+	assert_eq!(&job_digest[..], expected.as_slice());
+	
+	let (mut expected, mut output) = ([0; 100], [0; 100]);
+	Blake2Xb::auth(&mut expected, &data, raw).unwrap();
+	Blake2Xb::auth(&mut output, &data, &key).unwrap();
+	assert_eq!(output[..], expected[..]);
+}
+
+
+#[test]
+fn test_drop() {
+	let mut slot = MaybeUninit::new(Blake2bKey::new(&[0xa5; 64]).unwrap());
+	let memory = unsafe {
+		ptr::drop_in_place(slot.as_mut_ptr());
+		slice::from_raw_parts(slot.as_ptr() as *const u8, mem::size_of::<Blake2bKey>()).to_vec()
+	};
+	assert!(!memory.windows(8).any(|window| window == [0xa5; 8]));
+}
\ No newline at end of file
@@ -0,0 +1,84 @@
+use crypto_api_blake2::{ Blake2bParams, Blake2bState };
+include!("read_test_vectors.rs");
+
+
+/// Builds a 512-bit Blake2bp tree from `Blake2bState`s and sets the last node flags after absorbing
+/// the data
+fn blake2bp(data: &[u8], last_node: bool) -> Vec<u8> {
+	// Create the leaves and distribute the blocks among them
+	let mut params = Blake2bParams::new();
+	params.fanout(4).depth(2).inner_len(64);
+	
+	let mut leaves: Vec<Blake2bState> = (0..4)
+		.map(|i| params.node_offset(i).to_state().unwrap())
+		.collect();
+	for (i, block) in data.chunks(128).enumerate() { leaves[i % 4].update(block); }
+	leaves[3].set_last_node(last_node);
+	
+	// Create the root and absorb the leaf digests
+	let mut root = params.node_offset(0).node_depth(1).to_state().unwrap();
+	for leaf in leaves.iter_mut() {
+		let mut digest = [0; 64];
+		leaf.finish(&mut digest).unwrap();
+		root.update(&digest);
+	}
+	root.set_last_node(last_node);
+	
+	// Compute the root digest
+	let mut buf = vec![0; 64];
+	root.finish(&mut buf).unwrap();
+	buf
+}
+
+
+#[derive(Debug)]
+pub struct TestVector {
+	line: usize,
+	input_: Vec<u8>,
+	output: Vec<u8>
+}
+impl TestVector {
+	pub fn test(&self) {
+		// Compute the tree with and without the last node flags
+		assert_eq!(blake2bp(&self.input_, true), self.output, "@{} failed", self.line);
+		assert_ne!(blake2bp(&self.input_, false), self.output, "@{} failed", self.line);
+	}
+}
+#[test]
+fn test() {
+	// Read the Blake2bp test vectors
+	let vectors: Vec<TestVector> = read_test_vectors!(
+		"blake2bp_hash.txt"
+			=> TestVector{ line, input_, output }
+	);
+	// Test all vectors with a 512-bit digest
+	for vector in vectors.iter().filter(|v| v.output.len() == 64) { vector.test() }
+}
+
+#[test]
+fn test_tree() {
+	// Build a binary tree with depth 3 over three leaves; the last node of each level is only known
+	// after all of its data has been absorbed
+	let mut params = Blake2bParams::new();
+	params.fanout(2).depth(3).leaf_len(100).inner_len(64);
+	let mut node = |offset: u64, depth: u8, data: &[u8], last_node: bool| {
+		let mut state = params.node_offset(offset).node_depth(depth).to_state().unwrap();
+		state.update(data).set_last_node(last_node);
+		
+		let mut digest = vec![0; 64];
+		state.finish(&mut digest).unwrap();
+		digest
+	};
+	
+	// Hash the leaves, the inner nodes and the root
+	let leaf0 = node(0, 0, &[b'x'; 100], false);
+	let leaf1 = node(1, 0, &[b'y'; 100], false);
+	let leaf2 = node(2, 0, &[b'z'; 7], true);
+	let inner0 = node(0, 1, &[leaf0, leaf1].concat(), false);
+	let inner1 = node(1, 1, &leaf2, true);
+	let root = node(0, 2, &[inner0, inner1].concat(), true);
+	
+	// Compare the root against the reference implementation (via Python's `hashlib.blake2b`)
+	let expected = Vec::<u8>::parse_str(line!() as usize, "932d3320ff40bd80978083bedd4d7e2cc2c679c7796ccbc8d93f202c62b4ff7949baee98f47c160f9f770b64e0d40e8c19bc732c465d62b8ae3924f8c16fa6dc");
+	assert_eq!(root, expected);
+}
\ No newline at end of file
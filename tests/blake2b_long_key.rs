@@ -0,0 +1,85 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{
+	Blake2Error, Blake2b, Blake2bHasher, Blake2bKey,
+	crypto_api::mac::{ Mac, StreamingMac, StreamingVarlenMac, VarlenMac }
+};
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+pub struct TestVector {
+	line: usize,
+	key______: Vec<u8>,
+	prehashed: Vec<u8>,
+	input____: Vec<u8>,
+	mac______: Vec<u8>
+}
+impl TestVector {
+	pub fn test(&self) {
+		// Pre-hash the key
+		assert_eq!(Blake2bKey::prehash(&self.key______).as_slice(), &self.prehashed[..], "@{} failed", self.line);
+		
+		// One-shot
+		let blake2b = Blake2b::with_long_keys();
+		let mut buf = vec![0; self.mac______.len()];
+		match buf.len() {
+			64 => blake2b.auth(&mut buf, &self.input____, &self.key______).unwrap(),
+			_ => blake2b.varlen_auth(&mut buf, &self.input____, &self.key______).unwrap()
+		};
+		assert_eq!(buf, self.mac______, "@{} failed", self.line);
+		blake2b.varlen_verify(&self.mac______, &self.input____, &self.key______).unwrap();
+		
+		// Streaming
+		let mut streaming = Blake2b::with_long_keys();
+		match buf.len() {
+			64 => streaming.init(&self.key______).unwrap(),
+			_ => streaming.varlen_init(buf.len(), &self.key______).unwrap()
+		};
+		streaming.update(&self.input____).unwrap();
+		streaming.finish(&mut buf).unwrap();
+		assert_eq!(buf, self.mac______, "@{} failed", self.line);
+		
+		// The native keyed mode with the pre-hashed key
+		let key = Blake2bKey::prehash(&self.key______);
+		let mut hasher = Blake2bHasher::new_keyed(buf.len(), &key).unwrap();
+		hasher.update(&self.input____);
+		assert_eq!(hasher.finalize().as_slice(), &self.mac______[..], "@{} failed", self.line);
+	}
+}
+#[test]
+fn test() {
+	// Read test vectors
+	let vectors: Vec<TestVector> = read_test_vectors!(
+		"blake2b_long_key.txt"
+			=> TestVector{ line, key______, prehashed, input____, mac______ }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
+
+
+#[test]
+fn test_distinct() {
+	// The long key mode must differ from the native keyed mode even for short keys
+	let (mut native, mut long) = ([0; 64], [0; 64]);
+	Blake2b::mac().auth(&mut native, b"Testolope", b"Testolope").unwrap();
+	Blake2b::with_long_keys().auth(&mut long, b"Testolope", b"Testolope").unwrap();
+	assert_ne!(native, long);
+	
+	// The native keyed mode still rejects long keys
+	let err = Blake2b::mac().auth(&mut native, b"Testolope", &[0; 65]).unwrap_err();
+	assert_eq!(err.downcast_ref::<Blake2Error>(), Some(&Blake2Error::ApiMisuse("`key.len()` is too large")));
+	assert_eq!(Mac::info(&Blake2b::with_long_keys()).key_len_r, 1..usize::MAX);
+}
+
+
+#[test]
+fn test_api() {
+	let mut buf = [0; 64];
+	let err = Blake2b::with_long_keys().auth(&mut buf, b"Testolope", &[]).unwrap_err();
+	assert_eq!(err.downcast_ref::<Blake2Error>(), Some(&Blake2Error::ApiMisuse("`key.len()` is too small")));
+	
+	let err = StreamingMac::init(&mut Blake2b::with_long_keys(), &[]).unwrap_err();
+	assert_eq!(err.downcast_ref::<Blake2Error>(), Some(&Blake2Error::ApiMisuse("`key.len()` is too small")));
+}
\ No newline at end of file
@@ -0,0 +1,112 @@
+use crypto_api_blake2::{ Blake2Error, Blake2bJob, Blake2bMulti };
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+struct HashVector {
+	line: usize,
+	input_: Vec<u8>,
+	output: Vec<u8>
+}
+#[derive(Debug)]
+struct MacVector {
+	line: usize,
+	key__: Vec<u8>,
+	input: Vec<u8>,
+	mac__: Vec<u8>
+}
+
+
+#[test]
+fn test() {
+	// Read the hash vectors as unkeyed MAC vectors and append the MAC vectors
+	let mut vectors: Vec<MacVector> = read_test_vectors!("blake2b_hash.txt" => HashVector{ line, input_, output })
+		.into_iter()
+		.map(|v| MacVector { line: v.line, key__: Vec::new(), input: v.input_, mac__: v.output })
+		.collect();
+	vectors.extend(read_test_vectors!("blake2b_mac.txt" => MacVector{ line, key__, input, mac__ }));
+	
+	// Hash all vectors at once with interleaved hash and MAC jobs
+	let mut bufs: Vec<Vec<u8>> = vectors.iter().map(|v| vec![0; v.mac__.len()]).collect();
+	let mut jobs: Vec<Blake2bJob> = bufs.iter_mut().zip(vectors.iter())
+		.map(|(buf, v)| Blake2bJob::new_keyed(buf, &v.input, &v.key__))
+		.collect();
+	Blake2bMulti::hash(&mut jobs).unwrap();
+	
+	for (buf, v) in bufs.iter().zip(vectors.iter()) {
+		assert_eq!(buf, &v.mac__, "@{} failed", v.line);
+	}
+}
+
+
+#[test]
+#[cfg(feature = "std")]
+fn test_varlen() {
+	use crypto_api_blake2::Blake2b;
+	
+	// Create messages with different lengths, digest lengths and keys
+	let inputs: Vec<Vec<u8>> = (0..300).map(|len| (0..len).map(|b| b as u8).collect()).collect();
+	let keys: Vec<Vec<u8>> = (0..300).map(|i| vec![0x2a; [0, 1, 32, 64][i % 4]]).collect();
+	let out_lens: Vec<usize> = (0..300).map(|i| i % 64 + 1).collect();
+	
+	// Compute the digests using the multi-buffer API
+	let mut bufs: Vec<Vec<u8>> = out_lens.iter().map(|len| vec![0; *len]).collect();
+	let mut jobs: Vec<Blake2bJob> = bufs.iter_mut().zip(inputs.iter()).zip(keys.iter())
+		.map(|((buf, input), key)| Blake2bJob::new_keyed(buf, input, key))
+		.collect();
+	Blake2bMulti::hash(&mut jobs).unwrap();
+	
+	// Compare the digests with the varlen hash and MAC
+	let (hash, mac) = (Blake2b::varlen_hash(), Blake2b::varlen_mac());
+	for (i, buf) in bufs.iter().enumerate() {
+		let mut expected = vec![0; out_lens[i]];
+		match keys[i].len() {
+			0 => hash.varlen_hash(&mut expected, &inputs[i]).unwrap(),
+			_ => mac.varlen_auth(&mut expected, &inputs[i], &keys[i]).unwrap()
+		};
+		assert_eq!(buf, &expected, "Message {} failed", i);
+	}
+}
+
+
+#[derive(Debug)]
+struct ApiTestVector {
+	line: usize,
+	hash_len__: usize,
+	key_len___: usize,
+	error_desc: &'static str
+}
+impl ApiTestVector {
+	fn test(&self) {
+		// Create an invalid job between two valid ones and compare the error
+		let (mut buf0, mut buf1, mut buf2) = ([0; 64], vec![0; self.hash_len__], [0; 64]);
+		let (input, key) = (b"Testolope", vec![0; self.key_len___]);
+		let mut jobs = [
+			Blake2bJob::new(&mut buf0, input),
+			Blake2bJob::new_keyed(&mut buf1, input, &key),
+			Blake2bJob::new(&mut buf2, input)
+		];
+		match Blake2bMulti::hash(&mut jobs) {
+			Err(Blake2Error::ApiMisuse(desc)) => assert_eq!(
+				desc, self.error_desc,
+				"Invalid API-error description @{}", self.line
+			),
+			_ => panic!("Invalid result returned @{}", self.line)
+		}
+		
+		// Ensure that no job has been computed
+		assert_eq!(buf0, [0; 64], "A job has been computed @{}", self.line);
+	}
+}
+#[test]
+fn test_api() {
+	// Read test vectors
+	let vectors: Vec<ApiTestVector> = read_test_vectors!(
+		"blake2b_multi_api.txt" => ApiTestVector{ line, hash_len__, key_len___, error_desc }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+	
+	// An empty batch is valid
+	Blake2bMulti::hash(&mut []).unwrap();
+}
\ No newline at end of file
@@ -0,0 +1,120 @@
+use crypto_api_blake2::{ Blake2Error, Blake2bParams };
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+pub struct TestVector {
+	line: usize,
+	hash_len___: usize,
+	key________: Vec<u8>,
+	salt_______: Vec<u8>,
+	personal___: Vec<u8>,
+	fanout_____: usize,
+	depth______: usize,
+	leaf_len___: usize,
+	node_offset: u64,
+	node_depth_: usize,
+	inner_len__: usize,
+	last_node__: usize,
+	input______: Vec<u8>,
+	output_____: Vec<u8>
+}
+impl TestVector {
+	pub fn test(&self) {
+		// Create the state
+		let mut state = Blake2bParams::new()
+			.hash_len(self.hash_len___)
+			.key(&self.key________)
+			.salt(&self.salt_______)
+			.personal(&self.personal___)
+			.fanout(self.fanout_____ as u8)
+			.depth(self.depth______ as u8)
+			.leaf_len(self.leaf_len___ as u32)
+			.node_offset(self.node_offset)
+			.node_depth(self.node_depth_ as u8)
+			.inner_len(self.inner_len__)
+			.last_node(self.last_node__ != 0)
+			.to_state().unwrap();
+		
+		// Absorb data
+		let (input0, input1) = self.input______.split_at(self.input______.len() / 2);
+		state.update(input0).update(input1);
+		
+		// Compute hash
+		let mut buf = vec![0; self.hash_len___];
+		state.finish(&mut buf).unwrap();
+		assert_eq!(buf, self.output_____, "@{} failed", self.line);
+		
+		// Ensure that the state cannot be finalized twice
+		assert_eq!(
+			state.finish(&mut buf).unwrap_err(),
+			Blake2Error::ApiMisuse("The state has already been finalized"),
+			"@{} failed", self.line
+		);
+	}
+}
+#[test]
+fn test() {
+	// Read test vectors
+	let vectors: Vec<TestVector> = read_test_vectors!(
+		"blake2b_params.txt" => TestVector {
+			line, hash_len___, key________, salt_______, personal___, fanout_____, depth______,
+			leaf_len___, node_offset, node_depth_, inner_len__, last_node__, input______, output_____
+		}
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
+
+
+#[derive(Debug)]
+struct ApiTestVector {
+	line: usize,
+	hash_len___: usize,
+	key_len____: usize,
+	salt_len___: usize,
+	personal_len: usize,
+	fanout_____: usize,
+	depth______: usize,
+	leaf_len___: usize,
+	node_offset: u64,
+	node_depth_: usize,
+	inner_len__: usize,
+	error_desc_: &'static str
+}
+impl ApiTestVector {
+	fn test(&self) {
+		// Create the invalid parameters and compare the error
+		let result = Blake2bParams::new()
+			.hash_len(self.hash_len___)
+			.key(&vec![0; self.key_len____])
+			.salt(&vec![0; self.salt_len___])
+			.personal(&vec![0; self.personal_len])
+			.fanout(self.fanout_____ as u8)
+			.depth(self.depth______ as u8)
+			.leaf_len(self.leaf_len___ as u32)
+			.node_offset(self.node_offset)
+			.node_depth(self.node_depth_ as u8)
+			.inner_len(self.inner_len__)
+			.to_state();
+		match result {
+			Err(Blake2Error::ApiMisuse(desc)) => assert_eq!(
+				desc, self.error_desc_,
+				"Invalid API-error description @{}", self.line
+			),
+			_ => panic!("Invalid result returned @{}", self.line)
+		}
+	}
+}
+#[test]
+fn test_api() {
+	// Read test vectors
+	let vectors: Vec<ApiTestVector> = read_test_vectors!(
+		"blake2b_params_api.txt" => ApiTestVector {
+			line, hash_len___, key_len____, salt_len___, personal_len, fanout_____, depth______,
+			leaf_len___, node_offset, node_depth_, inner_len__, error_desc_
+		}
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
\ No newline at end of file
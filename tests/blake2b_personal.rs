@@ -0,0 +1,117 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{
+	Blake2Error, Blake2b,
+	crypto_api::{
+		hash::{ VarlenHash, StreamingHash, StreamingVarlenHash },
+		mac::{ VarlenMac, StreamingMac, StreamingVarlenMac }
+	}
+};
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+pub struct TestVector {
+	line: usize,
+	personal: Vec<u8>,
+	key_____: Vec<u8>,
+	input___: Vec<u8>,
+	output__: Vec<u8>
+}
+impl TestVector {
+	pub fn test(&self) {
+		match self.key_____.is_empty() {
+			true => { self.test_hash(); self.test_streaming_hash() },
+			false => { self.test_mac(); self.test_streaming_mac() }
+		}
+	}
+	fn test_hash(&self) {
+		// Create hasher
+		let hash = Blake2b::with_personal(&self.personal).unwrap();
+		
+		// Hash data and verify hash
+		let mut buf = vec![0; self.output__.len()];
+		hash.varlen_hash(&mut buf, &self.input___).unwrap();
+		assert_eq!(buf, self.output__, "@{} failed", self.line);
+	}
+	fn test_streaming_hash(&self) {
+		// Create hasher and initialize it
+		let mut hash = Blake2b::with_personal(&self.personal).unwrap();
+		StreamingVarlenHash::varlen_init(&mut hash, self.output__.len()).unwrap();
+		
+		// Absorb data
+		let (input0, input1) = self.input___.split_at(self.input___.len() / 3);
+		StreamingHash::update(&mut hash, input0).unwrap();
+		StreamingHash::update(&mut hash, input1).unwrap();
+		
+		// Compute hash
+		let mut buf = vec![0; self.output__.len()];
+		StreamingHash::finish(&mut hash, &mut buf).unwrap();
+		assert_eq!(buf, self.output__, "@{} failed", self.line);
+	}
+	fn test_mac(&self) {
+		// Create MAC
+		let mac = Blake2b::with_personal(&self.personal).unwrap();
+		
+		// Compute MAC
+		let mut buf = vec![0; self.output__.len()];
+		mac.varlen_auth(&mut buf, &self.input___, &self.key_____).unwrap();
+		assert_eq!(buf, self.output__, "@{} failed", self.line);
+	}
+	fn test_streaming_mac(&self) {
+		// Create MAC and initialize it
+		let mut mac = Blake2b::with_personal(&self.personal).unwrap();
+		StreamingVarlenMac::varlen_init(&mut mac, self.output__.len(), &self.key_____).unwrap();
+		
+		// Absorb data
+		let (input0, input1) = self.input___.split_at(self.input___.len() / 3);
+		StreamingMac::update(&mut mac, input0).unwrap();
+		StreamingMac::update(&mut mac, input1).unwrap();
+		
+		// Compute MAC
+		let mut buf = vec![0; self.output__.len()];
+		StreamingMac::finish(&mut mac, &mut buf).unwrap();
+		assert_eq!(buf, self.output__, "@{} failed", self.line);
+	}
+}
+#[test]
+fn test() {
+	// Read test vectors
+	let vectors: Vec<TestVector> = read_test_vectors!(
+		"blake2b_personal.txt"
+			=> TestVector{ line, personal, key_____, input___, output__ }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
+
+
+#[derive(Debug)]
+struct ApiTestVector {
+	line: usize,
+	personal_len: usize,
+	error_desc__: &'static str
+}
+impl ApiTestVector {
+	fn test(&self) {
+		// Create the invalid personalization string and compare the error
+		let personal = vec![0; self.personal_len];
+		match Blake2b::with_personal(&personal) {
+			Err(Blake2Error::ApiMisuse(desc)) => assert_eq!(
+				desc, self.error_desc__,
+				"Invalid API-error description @{}", self.line
+			),
+			_ => panic!("Invalid result returned @{}", self.line)
+		}
+	}
+}
+#[test]
+fn test_api() {
+	// Read test vectors
+	let vectors: Vec<ApiTestVector> = read_test_vectors!(
+		"blake2b_personal_api.txt"
+			=> ApiTestVector{ line, personal_len, error_desc__ }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
\ No newline at end of file
@@ -0,0 +1,165 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{
+	Blake2Error, Blake2b, Blake2bHmac, Blake2bParams, Blake2bState, Blake2bp, Blake2s, Blake2sHmac, Blake2sp,
+	crypto_api::{ hash::StreamingVarlenHash, mac::StreamingMac }
+};
+
+
+/// The messages to authenticate with the same state
+const MESSAGES: &[&[u8]] = &[b"", b"Testolope", &[0x2a; 128], &[0x17; 1000]];
+/// The MAC key
+const KEY: &[u8] = b"Testolope";
+
+
+#[test]
+fn test_streaming_mac() {
+	// Initialize the MAC once and compute a MAC for each message
+	let mut mac = Blake2b::default();
+	StreamingMac::init(&mut mac, KEY).unwrap();
+	for message in MESSAGES {
+		// Absorb garbage and reset the state to ensure that the reset discards it
+		StreamingMac::update(&mut mac, b"Garbage").unwrap();
+		mac.reset().unwrap();
+		
+		let (mut buf, mut expected) = ([0; 64], [0; 64]);
+		StreamingMac::update(&mut mac, message).unwrap();
+		StreamingMac::finish(&mut mac, &mut buf).unwrap();
+		Blake2b::mac().auth(&mut expected, message, KEY).unwrap();
+		assert_eq!(buf, expected);
+		
+		// Reset the finalized state
+		mac.reset().unwrap();
+	}
+}
+
+
+#[test]
+fn test_streaming_keyed() {
+	use crypto_api_blake2::crypto_api::mac::StreamingVarlenMac;
+	
+	/// Tests the reset of the keyed streaming MAC of `$type` with a fresh MAC of the same length
+	macro_rules! test {
+		($type:ident, $init:expr) => ({
+			let mut mac = $type::default();
+			$init(&mut mac);
+			for message in MESSAGES {
+				// Finish the state with an empty message in between to test the precomputed final block
+				for message in [message, &&b""[..]].iter() {
+					let (mut buf, mut expected) = ([0; 32], [0; 32]);
+					StreamingMac::update(&mut mac, message).unwrap();
+					StreamingMac::finish(&mut mac, &mut buf).unwrap();
+					
+					let mut fresh = $type::default();
+					$init(&mut fresh);
+					StreamingMac::update(&mut fresh, message).unwrap();
+					StreamingMac::finish(&mut fresh, &mut expected).unwrap();
+					assert_eq!(buf, expected, "{} failed", stringify!($type));
+					
+					mac.reset().unwrap();
+				}
+			}
+		});
+	}
+	test!(Blake2b, |mac: &mut Blake2b| StreamingVarlenMac::varlen_init(mac, 32, KEY).unwrap());
+	test!(Blake2s, |mac: &mut Blake2s| StreamingVarlenMac::varlen_init(mac, 32, KEY).unwrap());
+	test!(Blake2bp, |mac: &mut Blake2bp| StreamingVarlenMac::varlen_init(mac, 32, KEY).unwrap());
+	test!(Blake2sp, |mac: &mut Blake2sp| StreamingVarlenMac::varlen_init(mac, 32, KEY).unwrap());
+	test!(Blake2sHmac, |mac: &mut Blake2sHmac| StreamingMac::init(mac, KEY).unwrap());
+	
+	// HMAC-Blake2b has a fixed MAC length of 64 bytes
+	let mut mac = Blake2bHmac::default();
+	StreamingMac::init(&mut mac, KEY).unwrap();
+	for message in MESSAGES.iter().chain(&[&b""[..]]) {
+		let (mut buf, mut expected) = ([0; 64], [0; 64]);
+		StreamingMac::update(&mut mac, message).unwrap();
+		StreamingMac::finish(&mut mac, &mut buf).unwrap();
+		Blake2bHmac::mac().auth(&mut expected, message, KEY).unwrap();
+		assert_eq!(buf, expected);
+		
+		mac.reset().unwrap();
+	}
+}
+
+
+#[test]
+fn test_streaming_varlen_hash() {
+	/// Tests the reset of the streaming varlen hash of `$type`
+	macro_rules! test {
+		($type:ident) => ({
+			let mut hash = $type::default();
+			hash.varlen_init(17).unwrap();
+			for message in MESSAGES {
+				let (mut buf, mut expected) = ([0; 17], [0; 17]);
+				hash.update(message).unwrap();
+				hash.finish(&mut buf).unwrap();
+				$type::varlen_hash().varlen_hash(&mut expected, message).unwrap();
+				assert_eq!(buf, expected, "{} failed", stringify!($type));
+				
+				// The state is uninitialized until it is reset
+				assert!(hash.update(b"Testolope").is_err());
+				hash.reset().unwrap();
+			}
+		});
+	}
+	test!(Blake2b);
+	test!(Blake2s);
+	test!(Blake2bp);
+	test!(Blake2sp);
+}
+
+
+#[test]
+fn test_state() {
+	let mut state = Blake2bParams::new().hash_len(32).key(KEY).to_state().unwrap();
+	for message in MESSAGES {
+		let (mut buf, mut expected) = ([0; 32], [0; 32]);
+		state.update(message).finish(&mut buf).unwrap();
+		Blake2b::varlen_mac().varlen_auth(&mut expected, message, KEY).unwrap();
+		assert_eq!(buf, expected);
+		
+		state.reset();
+	}
+}
+
+
+#[test]
+fn test_precompressed() {
+	let mut state = Blake2bParams::new().hash_len(32).key(KEY).last_node(true).to_state().unwrap();
+	
+	// The fresh state buffers the key block whereas the reset state has already compressed it
+	let exported = state.export().unwrap();
+	assert_eq!((exported[6], exported[7]), (128, 0b01));
+	let exported = state.update(b"Garbage").reset().export().unwrap();
+	assert_eq!((exported[6], exported[7]), (0, 0b11));
+	assert_eq!(exported[72..80], 128u64.to_le_bytes());
+	
+	// Finish the reset state and its imported copy with and without the last node flag
+	for message in MESSAGES {
+		for last_node in [false, true].iter().copied() {
+			let mut expected = [0; 32];
+			let mut params = Blake2bParams::new();
+			params.hash_len(32).key(KEY).last_node(last_node).to_state().unwrap()
+				.update(message).finish(&mut expected).unwrap();
+			
+			let mut buf = [0; 32];
+			state.reset().set_last_node(last_node).update(message).finish(&mut buf).unwrap();
+			assert_eq!(buf, expected);
+			
+			let mut buf = [0; 32];
+			Blake2bState::import(&exported).unwrap().set_last_node(last_node).update(message)
+				.finish(&mut buf).unwrap();
+			assert_eq!(buf, expected);
+		}
+	}
+}
+
+
+#[test]
+fn test_api() {
+	// Reset an uninitialized state
+	assert_eq!(Blake2b::default().reset(), Err(Blake2Error::ApiMisuse("The state is not initialized")));
+	assert_eq!(Blake2s::default().reset(), Err(Blake2Error::ApiMisuse("The state is not initialized")));
+	assert_eq!(Blake2bp::default().reset(), Err(Blake2Error::ApiMisuse("The state is not initialized")));
+	assert_eq!(Blake2sp::default().reset(), Err(Blake2Error::ApiMisuse("The state is not initialized")));
+}
\ No newline at end of file
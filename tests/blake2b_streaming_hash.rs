@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use crypto_api_blake2::{ Blake2Error, Blake2b };
 include!("read_test_vectors.rs");
 
@@ -63,6 +65,25 @@ fn test() {
 }
 
 
+#[test]
+fn test_chunks() {
+	// Hash the same data in chunks that cross the block boundaries in different ways
+	let data: Vec<u8> = (0..2048).map(|b| b as u8).collect();
+	let mut expected = vec![0; 64];
+	Blake2b::hash().hash(&mut expected, &data).unwrap();
+	
+	for chunk_len in [1, 127, 128, 129, 256, 257, 1000].iter() {
+		let mut hash = Blake2b::streaming_hash();
+		hash.init().unwrap();
+		for chunk in data.chunks(*chunk_len) { hash.update(chunk).unwrap() }
+		
+		let mut buf = vec![0; 64];
+		hash.finish(&mut buf).unwrap();
+		assert_eq!(buf, expected, "Chunk length {} failed", chunk_len);
+	}
+}
+
+
 #[derive(Debug)]
 struct ApiTestVector {
 	line: usize,
@@ -0,0 +1,147 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{ Blake2Error, Blake2bParams };
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+pub struct TestVector {
+	line: usize,
+	hash_len_: usize,
+	key______: Vec<u8>,
+	salt_____: Vec<u8>,
+	personal_: Vec<u8>,
+	fanout___: usize,
+	depth____: usize,
+	leaf_len_: usize,
+	inner_len: usize,
+	chunk_len: usize,
+	input____: Vec<u8>,
+	output___: Vec<u8>
+}
+impl TestVector {
+	pub fn test(&self) {
+		// Create the tree
+		let mut tree = Blake2bParams::new()
+			.hash_len(self.hash_len_)
+			.key(&self.key______)
+			.salt(&self.salt_____)
+			.personal(&self.personal_)
+			.fanout(self.fanout___ as u8)
+			.depth(self.depth____ as u8)
+			.leaf_len(self.leaf_len_ as u32)
+			.inner_len(self.inner_len)
+			.to_tree().unwrap();
+		
+		// Add the leaves
+		for leaf in self.input____.chunks(self.chunk_len) { tree.add_leaf(leaf).unwrap(); }
+		
+		// Compute the root
+		let mut buf = vec![0; self.hash_len_];
+		tree.finish(&mut buf).unwrap();
+		assert_eq!(buf, self.output___, "@{} failed", self.line);
+		
+		// Ensure that the tree cannot be finalized twice
+		assert_eq!(
+			tree.finish(&mut buf).unwrap_err(),
+			Blake2Error::ApiMisuse("The tree has already been finalized"),
+			"@{} failed", self.line
+		);
+	}
+}
+#[test]
+fn test() {
+	// Read test vectors
+	let vectors: Vec<TestVector> = read_test_vectors!(
+		"blake2b_tree.txt" => TestVector {
+			line, hash_len_, key______, salt_____, personal_, fanout___, depth____, leaf_len_,
+			inner_len, chunk_len, input____, output___
+		}
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
+
+
+#[test]
+fn test_leaves() {
+	// Create a tree with up to 4 leaves with 16 bytes each
+	let tree = || Blake2bParams::new().fanout(2).depth(3).leaf_len(16).inner_len(64).to_tree().unwrap();
+	
+	// Add a leaf that is too large
+	assert_eq!(
+		tree().add_leaf(&[0; 17]).err(),
+		Some(Blake2Error::ApiMisuse("`leaf.len()` is too large"))
+	);
+	
+	// Add a leaf after a short leaf
+	let mut short = tree();
+	short.add_leaf(&[0; 15]).unwrap();
+	assert_eq!(
+		short.add_leaf(&[0; 16]).err(),
+		Some(Blake2Error::ApiMisuse("Only the last leaf may be shorter than the leaf length"))
+	);
+	
+	// Add more leaves than the tree can hold
+	let mut full = tree();
+	for _ in 0..4 { full.add_leaf(&[0; 16]).unwrap(); }
+	assert_eq!(
+		full.add_leaf(&[0; 16]).err(),
+		Some(Blake2Error::ApiMisuse("The tree exceeds the maximal depth"))
+	);
+	
+	// Add a leaf to a finalized tree
+	let mut finalized = tree();
+	finalized.finish(&mut [0; 64]).unwrap();
+	assert_eq!(
+		finalized.add_leaf(&[0; 16]).err(),
+		Some(Blake2Error::ApiMisuse("The tree has already been finalized"))
+	);
+}
+
+
+#[derive(Debug)]
+struct ApiTestVector {
+	line: usize,
+	hash_len___: usize,
+	fanout_____: usize,
+	depth______: usize,
+	node_offset: u64,
+	node_depth_: usize,
+	inner_len__: usize,
+	last_node__: usize,
+	error_desc_: &'static str
+}
+impl ApiTestVector {
+	fn test(&self) {
+		// Create the invalid tree and compare the error
+		let result = Blake2bParams::new()
+			.hash_len(self.hash_len___)
+			.fanout(self.fanout_____ as u8)
+			.depth(self.depth______ as u8)
+			.node_offset(self.node_offset)
+			.node_depth(self.node_depth_ as u8)
+			.inner_len(self.inner_len__)
+			.last_node(self.last_node__ != 0)
+			.to_tree();
+		match result {
+			Err(Blake2Error::ApiMisuse(desc)) => assert_eq!(
+				desc, self.error_desc_,
+				"Invalid API-error description @{}", self.line
+			),
+			_ => panic!("Invalid result returned @{}", self.line)
+		}
+	}
+}
+#[test]
+fn test_api() {
+	// Read test vectors
+	let vectors: Vec<ApiTestVector> = read_test_vectors!(
+		"blake2b_tree_api.txt" => ApiTestVector {
+			line, hash_len___, fanout_____, depth______, node_offset, node_depth_, inner_len__,
+			last_node__, error_desc_
+		}
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
\ No newline at end of file
@@ -0,0 +1,121 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{
+	Blake2Error, Blake2b, Blake2bFixed, Blake2bHasher, Blake2bParams,
+	crypto_api::mac::{ StreamingMac, StreamingVarlenMac }
+};
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+pub struct TestVector {
+	line: usize,
+	key__: Vec<u8>,
+	input: Vec<u8>,
+	mac__: Vec<u8>
+}
+impl TestVector {
+	pub fn test(&self) {
+		// The valid tag must be accepted
+		assert!(self.verify(&self.mac__).iter().all(|result| *result == Ok(())), "@{} failed", self.line);
+		
+		// Altered, truncated and extended tags must be rejected (empty tags are tested in `test_api`)
+		let mut tags = Vec::new();
+		for i in [0, self.mac__.len() / 2, self.mac__.len() - 1].iter() {
+			let mut altered = self.mac__.clone();
+			altered[*i] ^= 0x01;
+			tags.push(altered);
+		}
+		tags.push(self.mac__[..self.mac__.len() - 1].to_vec());
+		tags.push(self.mac__[..1].to_vec());
+		tags.push([&self.mac__[..], &[0]].concat());
+		for tag in tags {
+			let is_invalid = |result: &Result<(), Blake2Error>| *result == Err(Blake2Error::InvalidTag);
+			assert!(self.verify(&tag).iter().all(is_invalid), "@{} failed", self.line);
+		}
+	}
+	/// Verifies `tag` using all verification APIs
+	fn verify(&self, tag: &[u8]) -> Vec<Result<(), Blake2Error>> {
+		let len = self.mac__.len();
+		
+		// One-shot (the constant length API only for 64 byte MACs)
+		let oneshot = match len {
+			64 => Blake2b::default().verify(tag, &self.input, &self.key__),
+			_ => Blake2b::default().varlen_verify(tag, &self.input, &self.key__)
+		};
+		
+		// Streaming
+		let mut streaming = Blake2b::default();
+		streaming.varlen_init(len, &self.key__).unwrap();
+		streaming.update(&self.input).unwrap();
+		let streaming = streaming.finish_verify(tag);
+		
+		let mut state = Blake2bParams::new().hash_len(len).key(&self.key__).to_state().unwrap();
+		let state = state.update(&self.input).finish_verify(tag);
+		
+		let mut hasher = Blake2bHasher::new_keyed(len, &self.key__).unwrap();
+		let hasher = hasher.update(&self.input).clone().verify(tag);
+		
+		// The fixed length API (only tested for 64 byte MACs)
+		let mut results = vec![oneshot, streaming, state, hasher];
+		if len == 64 {
+			results.push(Blake2bFixed::<64>::verify_auth(tag, &self.input, &self.key__));
+		}
+		results
+	}
+}
+#[test]
+fn test() {
+	// Read test vectors
+	let vectors: Vec<TestVector> = read_test_vectors!(
+		"blake2b_mac.txt"
+			=> TestVector{ line, key__, input, mac__ }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
+
+
+#[test]
+fn test_api() {
+	let tag = [0; 64];
+	assert_eq!(
+		Blake2b::default().verify(&tag, b"Testolope", &[]).err(),
+		Some(Blake2Error::ApiMisuse("`key.len()` is too small"))
+	);
+	assert_eq!(
+		Blake2b::default().varlen_verify(&tag, b"Testolope", &[0; 65]).err(),
+		Some(Blake2Error::ApiMisuse("`key.len()` is too large"))
+	);
+	assert_eq!(
+		Blake2b::default().finish_verify(&tag).err(),
+		Some(Blake2Error::ApiMisuse("The MAC is not initialized"))
+	);
+	
+	// A tag with an invalid MAC length is an API misuse for the variable length API and an invalid tag
+	// otherwise
+	assert_eq!(
+		Blake2b::default().varlen_verify(&[], b"Testolope", b"Testolope").err(),
+		Some(Blake2Error::ApiMisuse("`tag.len()` is too small"))
+	);
+	assert_eq!(
+		Blake2b::default().varlen_verify(&[0; 65], b"Testolope", b"Testolope").err(),
+		Some(Blake2Error::ApiMisuse("`tag.len()` is too large"))
+	);
+	assert_eq!(
+		Blake2b::default().verify(&[], b"Testolope", b"Testolope").err(),
+		Some(Blake2Error::InvalidTag)
+	);
+	assert_eq!(
+		Blake2bHasher::new_keyed(64, b"Testolope").unwrap().verify(&[]).err(),
+		Some(Blake2Error::InvalidTag)
+	);
+	
+	// A finalized state cannot be verified again
+	let mut state = Blake2bParams::new().key(b"Testolope").to_state().unwrap();
+	let _ = state.finish_verify(&tag);
+	assert_eq!(
+		state.finish_verify(&tag).err(),
+		Some(Blake2Error::ApiMisuse("The state has already been finalized"))
+	);
+}
\ No newline at end of file
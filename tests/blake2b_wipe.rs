@@ -0,0 +1,40 @@
+use crypto_api_blake2::wipe;
+
+
+#[test]
+fn test() {
+	// Wipe live values of the integer and boolean types
+	let (mut byte, mut word, mut dword, mut size, mut flag) = (0xa5u8, u32::MAX, u64::MAX, usize::MAX, true);
+	wipe(&mut byte);
+	wipe(&mut word);
+	wipe(&mut dword);
+	wipe(&mut size);
+	wipe(&mut flag);
+	assert_eq!((byte, word, dword, size, flag), (0, 0, 0, 0, false));
+	
+	// Wipe live (nested) arrays like the state buffers and the precomputed chain values
+	let (mut buf, mut empty) = ([0xa5u8; 128], [[u64::MAX; 8]; 2]);
+	wipe(&mut buf);
+	wipe(&mut empty);
+	assert_eq!(buf, [0; 128]);
+	assert_eq!(empty, [[0; 8]; 2]);
+}
+
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn test_simd() {
+	use core::{ arch::x86_64::{ __m128i, __m256i, __m512i }, mem };
+	
+	// Wipe live SIMD vectors like the work registers of the compression backends
+	// Safety: All bit patterns are valid SIMD vectors and integer arrays
+	let mut xmm: __m128i = unsafe { mem::transmute([u64::MAX; 2]) };
+	let mut ymm: __m256i = unsafe { mem::transmute([u64::MAX; 4]) };
+	let mut zmm: __m512i = unsafe { mem::transmute([u64::MAX; 8]) };
+	wipe(&mut xmm);
+	wipe(&mut ymm);
+	wipe(&mut zmm);
+	assert_eq!(unsafe { mem::transmute::<__m128i, [u64; 2]>(xmm) }, [0; 2]);
+	assert_eq!(unsafe { mem::transmute::<__m256i, [u64; 4]>(ymm) }, [0; 4]);
+	assert_eq!(unsafe { mem::transmute::<__m512i, [u64; 8]>(zmm) }, [0; 8]);
+}
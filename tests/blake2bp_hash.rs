@@ -0,0 +1,138 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{ Blake2Error, Blake2bp };
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+pub struct TestVector {
+	line: usize,
+	input_: Vec<u8>,
+	output: Vec<u8>
+}
+impl TestVector {
+	pub fn test(&self) {
+		match self.output.len() {
+			64 => self.test_constlen(),
+			_ => self.test_varlen()
+		}
+		self.test_streaming();
+	}
+	fn test_constlen(&self) {
+		// Create hasher
+		let hash = Blake2bp::hash();
+		
+		// Hash data and verify hash
+		let mut buf = vec![0; 64];
+		hash.hash(&mut buf, &self.input_).unwrap();
+		assert_eq!(buf, self.output, "@{} failed", self.line);
+	}
+	fn test_varlen(&self) {
+		// Create hasher
+		let hash = Blake2bp::varlen_hash();
+		
+		// Hash data and verify hash
+		let mut buf = vec![0; self.output.len()];
+		hash.varlen_hash(&mut buf, &self.input_).unwrap();
+		assert_eq!(buf, self.output, "@{} failed", self.line);
+	}
+	fn test_streaming(&self) {
+		// Create hasher and initialize it
+		let mut hash = Blake2bp::streaming_varlen_hash();
+		hash.varlen_init(self.output.len()).unwrap();
+		
+		// Absorb data in chunks that are not aligned to the stripes
+		for chunk in self.input_.chunks(333) { hash.update(chunk).unwrap() }
+		
+		// Compute hash
+		let mut buf = vec![0; self.output.len()];
+		hash.finish(&mut buf).unwrap();
+		assert_eq!(buf, self.output, "@{} failed", self.line);
+	}
+}
+#[test]
+fn test() {
+	// Read test vectors
+	let vectors: Vec<TestVector> = read_test_vectors!(
+		"blake2bp_hash.txt"
+			=> TestVector{ line, input_, output }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
+
+#[test]
+fn test_large() {
+	// Create a large input that is processed by parallel threads if it is hashed at once
+	let input: Vec<u8> = (0..3 * 1024 * 1024 + 77).map(|i| (i % 251) as u8).collect();
+	// The expected digest (cross-checked against the `blake2b_simd`-crate)
+	let output = Vec::<u8>::parse_str(line!() as usize, "0ce4aa110ad67efc723da521ca03d292b52a54ad69e10b64031bcdf7b3d119c367c6503804abe9ec87d486ec31b325b8785f4c71b9b9bf9efad3fa5061906986");
+	
+	// Hash the input at once
+	let mut buf = vec![0; 64];
+	Blake2bp::hash().hash(&mut buf, &input).unwrap();
+	assert_eq!(buf, output);
+	
+	// Hash the input in small chunks
+	let mut hash = Blake2bp::streaming_hash();
+	hash.init().unwrap();
+	for chunk in input.chunks(4099) { hash.update(chunk).unwrap() }
+	hash.finish(&mut buf).unwrap();
+	assert_eq!(buf, output);
+}
+
+
+#[derive(Debug)]
+struct ApiTestVector {
+	line: usize,
+	output_len: usize,
+	error_desc: &'static str
+}
+impl ApiTestVector {
+	fn test(&self) {
+		match self.error_desc {
+			"`buf.len()` is invalid" => self.test_constlen(),
+			_ => self.test_varlen()
+		}
+	}
+	fn test_constlen(&self) {
+		// Create hasher
+		let hash = Blake2bp::hash();
+		
+		// Create the invalid output buffer and compare the error
+		let mut buf = vec![0; self.output_len];
+		let err = hash.hash(&mut buf, b"Testolope").unwrap_err();
+		match err.downcast_ref::<Blake2Error>() {
+			Some(Blake2Error::ApiMisuse(desc)) => assert_eq!(
+				*desc, self.error_desc,
+				"Invalid API-error description @{}", self.line
+			),
+			_ => panic!("Invalid error returned @{}", self.line)
+		}
+	}
+	fn test_varlen(&self) {
+		// Create hasher
+		let hash = Blake2bp::varlen_hash();
+		
+		// Create the invalid output buffer and compare the error
+		let mut buf = vec![0; self.output_len];
+		let err = hash.varlen_hash(&mut buf, b"Testolope").unwrap_err();
+		match err.downcast_ref::<Blake2Error>() {
+			Some(Blake2Error::ApiMisuse(desc)) => assert_eq!(
+				*desc, self.error_desc,
+				"Invalid API-error description @{}", self.line
+			),
+			_ => panic!("Invalid error returned @{}", self.line)
+		}
+	}
+}
+#[test]
+fn test_api() {
+	// Read test vectors
+	let vectors: Vec<ApiTestVector> = read_test_vectors!(
+		"blake2bp_hash_api.txt"
+			=> ApiTestVector{ line, output_len, error_desc }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
\ No newline at end of file
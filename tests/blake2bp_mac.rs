@@ -0,0 +1,133 @@
+#![cfg(feature = "std")]
+
+use crypto_api_blake2::{ Blake2Error, Blake2bp };
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+pub struct TestVector {
+	line: usize,
+	key__: Vec<u8>,
+	input: Vec<u8>,
+	mac__: Vec<u8>
+}
+impl TestVector {
+	pub fn test(&self) {
+		match self.mac__.len() {
+			64 => self.test_constlen(),
+			_ => self.test_varlen()
+		}
+		self.test_streaming();
+	}
+	fn test_constlen(&self) {
+		// Create MAC
+		let mac = Blake2bp::mac();
+		
+		// Derive MAC
+		let mut buf = vec![0; 64];
+		mac.auth(&mut buf, &self.input, &self.key__).unwrap();
+		assert_eq!(buf, self.mac__, "@{} failed", self.line);
+	}
+	fn test_varlen(&self) {
+		// Create MAC
+		let mac = Blake2bp::varlen_mac();
+		
+		// Derive MAC
+		let mut buf = vec![0; self.mac__.len()];
+		mac.varlen_auth(&mut buf, &self.input, &self.key__).unwrap();
+		assert_eq!(buf, self.mac__, "@{} failed", self.line);
+	}
+	fn test_streaming(&self) {
+		// Create MAC and initialize it
+		let mut mac = Blake2bp::streaming_varlen_mac();
+		mac.varlen_init(self.mac__.len(), &self.key__).unwrap();
+		
+		// Absorb data in chunks that are not aligned to the leaf blocks
+		for chunk in self.input.chunks(77) { mac.update(chunk).unwrap() }
+		
+		// Compute MAC
+		let mut buf = vec![0; self.mac__.len()];
+		mac.finish(&mut buf).unwrap();
+		assert_eq!(buf, self.mac__, "@{} failed", self.line);
+	}
+}
+#[test]
+fn test() {
+	// Read test vectors
+	let vectors: Vec<TestVector> = read_test_vectors!(
+		"blake2bp_mac.txt"
+			=> TestVector{ line, key__, input, mac__ }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
+
+
+#[derive(Debug)]
+struct ApiTestVector {
+	line: usize,
+	test_name_: &'static str,
+	input_len_: usize,
+	key_len___: usize,
+	mac_len___: usize,
+	error_desc: &'static str
+}
+impl ApiTestVector {
+	fn test(&self) {
+		match self.test_name_ {
+			"test_constlen" => self.test_constlen(),
+			"test_varlen" => self.test_varlen(),
+			_ => panic!("Invalid test name @{}", self.line)
+		}
+	}
+	fn test_constlen(&self) {
+		// Create MAC
+		let mac = Blake2bp::mac();
+		
+		// Create parameters
+		let input = vec![0; self.input_len_];
+		let key = vec![0; self.key_len___];
+		let mut buf = vec![0; self.mac_len___];
+		
+		// Test API
+		let err = mac.auth(&mut buf, &input, &key).unwrap_err();
+		match err.downcast_ref::<Blake2Error>() {
+			Some(Blake2Error::ApiMisuse(desc)) => assert_eq!(
+				*desc, self.error_desc,
+				"Invalid API-error description @{}", self.line
+			),
+			_ => panic!("Invalid error returned @{}", self.line)
+		}
+	}
+	fn test_varlen(&self) {
+		// Create MAC
+		let mac = Blake2bp::varlen_mac();
+		
+		// Create parameters
+		let input = vec![0; self.input_len_];
+		let key = vec![0; self.key_len___];
+		let mut buf = vec![0; self.mac_len___];
+		
+		// Test API
+		let err = mac.varlen_auth(&mut buf, &input, &key).unwrap_err();
+		match err.downcast_ref::<Blake2Error>() {
+			Some(Blake2Error::ApiMisuse(desc)) => assert_eq!(
+				*desc, self.error_desc,
+				"Invalid API-error description @{}", self.line
+			),
+			_ => panic!("Invalid error returned @{}", self.line)
+		}
+	}
+}
+#[test]
+fn test_api() {
+	// Read test vectors
+	let vectors: Vec<ApiTestVector> = read_test_vectors!(
+		"blake2bp_mac_api.txt" => ApiTestVector {
+			line, test_name_, input_len_, key_len___,
+			mac_len___, error_desc
+		}
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
\ No newline at end of file
@@ -0,0 +1,65 @@
+use crypto_api_blake2::{ Blake2Error, Blake2sHasher };
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+struct HashVector {
+	line: usize,
+	input_: Vec<u8>,
+	output: Vec<u8>
+}
+#[derive(Debug)]
+struct MacVector {
+	line: usize,
+	key__: Vec<u8>,
+	input: Vec<u8>,
+	mac__: Vec<u8>
+}
+
+
+#[test]
+fn test() {
+	// Test the hash vectors
+	for v in read_test_vectors!("blake2s_hash.txt" => HashVector{ line, input_, output }) {
+		let mut hasher = Blake2sHasher::new(v.output.len()).unwrap();
+		for chunk in v.input_.chunks(100) { hasher.update(chunk); }
+		assert_eq!(hasher.finalize().as_slice(), v.output.as_slice(), "@{} failed", v.line);
+	}
+	
+	// Test the MAC vectors
+	for v in read_test_vectors!("blake2s_mac.txt" => MacVector{ line, key__, input, mac__ }) {
+		let mut hasher = Blake2sHasher::new_keyed(v.mac__.len(), &v.key__).unwrap();
+		hasher.update(&v.input);
+		assert_eq!(hasher.clone().verify(&v.mac__), Ok(()), "@{} failed", v.line);
+		assert_eq!(&*hasher.finalize(), v.mac__.as_slice(), "@{} failed", v.line);
+	}
+}
+#[test]
+fn test_digest_eq() {
+	// Compare digests with the same and different contents and lengths
+	let digest = |len: usize, data: &[u8]| {
+		let mut hasher = Blake2sHasher::new(len).unwrap();
+		hasher.update(data);
+		hasher.finalize()
+	};
+	assert_eq!(digest(16, b"Testolope"), digest(16, b"Testolope"));
+	assert_ne!(digest(16, b"Testolope"), digest(16, b"Testolopf"));
+	assert_ne!(digest(16, b"Testolope"), digest(17, b"Testolope"));
+}
+
+
+#[test]
+fn test_api() {
+	// Invalid digest and key lengths
+	let error = |desc| Some(Blake2Error::ApiMisuse(desc));
+	assert_eq!(Blake2sHasher::new(0).err(), error("`len` is too small"));
+	assert_eq!(Blake2sHasher::new(33).err(), error("`len` is too large"));
+	assert_eq!(Blake2sHasher::new_keyed(33, &[0; 16]).err(), error("`len` is too large"));
+	assert_eq!(Blake2sHasher::new_keyed(32, &[]).err(), error("`key.len()` is too small"));
+	assert_eq!(Blake2sHasher::new_keyed(32, &[0; 33]).err(), error("`key.len()` is too large"));
+	
+	// Invalid tag
+	let mut hasher = Blake2sHasher::new_keyed(32, b"Testolope").unwrap();
+	hasher.update(b"Testolope");
+	assert_eq!(hasher.verify(&[0; 32]), Err(Blake2Error::InvalidTag));
+}
@@ -0,0 +1,115 @@
+use crypto_api_blake2::{ Blake2Error, Blake2Xb };
+include!("read_test_vectors.rs");
+
+
+#[derive(Debug)]
+pub struct TestVector {
+	line: usize,
+	key___: Vec<u8>,
+	input_: Vec<u8>,
+	output: Vec<u8>
+}
+impl TestVector {
+	pub fn test(&self) {
+		self.test_oneshot();
+		self.test_reader();
+	}
+	fn test_oneshot(&self) {
+		// Compute the output
+		let mut buf = vec![0; self.output.len()];
+		match self.key___.len() {
+			0 => Blake2Xb::hash(&mut buf, &self.input_).unwrap(),
+			_ => Blake2Xb::auth(&mut buf, &self.input_, &self.key___).unwrap()
+		}
+		assert_eq!(buf, self.output, "@{} failed", self.line);
+	}
+	fn test_reader(&self) {
+		// Create the state and absorb data in chunks
+		let mut state = match self.key___.len() {
+			0 => Blake2Xb::new(self.output.len() as u32).unwrap(),
+			_ => Blake2Xb::new_keyed(self.output.len() as u32, &self.key___).unwrap()
+		};
+		for chunk in self.input_.chunks(77) { state.update(chunk); }
+		
+		// Squeeze the output in chunks that are not aligned to the blocks
+		let mut reader = state.finish();
+		let mut buf = Vec::new();
+		let mut chunk = [0; 23];
+		loop {
+			match reader.squeeze(&mut chunk) {
+				0 => break,
+				len => buf.extend_from_slice(&chunk[..len])
+			}
+		}
+		assert_eq!(buf, self.output, "@{} failed", self.line);
+		assert_eq!(reader.remaining(), 0, "@{} failed", self.line);
+	}
+}
+#[test]
+fn test() {
+	// Read test vectors
+	let vectors: Vec<TestVector> = read_test_vectors!(
+		"blake2xb.txt" => TestVector{ line, key___, input_, output }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+}
+
+
+#[cfg(feature = "std")]
+#[test]
+fn test_read() {
+	use std::io::Read;
+	
+	// Compute the output using the one-shot API
+	let mut expected = vec![0; 4711];
+	Blake2Xb::hash(&mut expected, b"Testolope").unwrap();
+	
+	// Read the output using `std::io::Read`
+	let mut state = Blake2Xb::new(4711).unwrap();
+	state.update(b"Testolope");
+	
+	let mut buf = Vec::new();
+	state.finish().read_to_end(&mut buf).unwrap();
+	assert_eq!(buf, expected);
+}
+
+
+#[derive(Debug)]
+struct ApiTestVector {
+	line: usize,
+	xof_len___: usize,
+	key_len___: usize,
+	error_desc: &'static str
+}
+impl ApiTestVector {
+	fn test(&self) {
+		// Create the invalid state and compare the error
+		let result = match self.key_len___ {
+			0 => Blake2Xb::new(self.xof_len___ as u32),
+			key_len => Blake2Xb::new_keyed(self.xof_len___ as u32, &vec![0; key_len])
+		};
+		match result {
+			Err(Blake2Error::ApiMisuse(desc)) => assert_eq!(
+				desc, self.error_desc,
+				"Invalid API-error description @{}", self.line
+			),
+			_ => panic!("Invalid result returned @{}", self.line)
+		}
+	}
+}
+#[test]
+fn test_api() {
+	// Read test vectors
+	let vectors: Vec<ApiTestVector> = read_test_vectors!(
+		"blake2xb_api.txt" => ApiTestVector{ line, xof_len___, key_len___, error_desc }
+	);
+	// Test all vectors
+	for vector in vectors { vector.test() }
+	
+	// Test the one-shot API with an empty buffer
+	assert_eq!(
+		Blake2Xb::hash(&mut[], b"Testolope").unwrap_err(),
+		Blake2Error::ApiMisuse("`buf.len()` is too small")
+	);
+}
\ No newline at end of file